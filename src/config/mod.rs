@@ -0,0 +1,272 @@
+use crate::health::{
+    CheckSettings, DISK_FULL_CRITICAL, DISK_FULL_WARNING, PRESSURE_CRITICAL, PRESSURE_WARNING,
+};
+use crate::models::{CollectOptions, DiskHistory};
+use crate::probes::PublicIpResolver;
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Name of the configuration file inside the config directory
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Application configuration loaded from `config.toml`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub network: NetworkConfig,
+    pub history: HistoryConfig,
+    pub pressure: PressureConfig,
+}
+
+/// Settings for external network probes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// Allow probes that contact external services (public IP, bandwidth)
+    pub online: bool,
+    /// Timeout in seconds for each public IP lookup
+    pub public_ip_timeout_secs: u64,
+    /// Timeout in seconds for the bandwidth benchmark
+    pub bandwidth_timeout_secs: u64,
+    /// Public IP resolvers, tried in order
+    pub public_ip_resolvers: Vec<PublicIpResolver>,
+    /// Bandwidth benchmark downloads, tried in order
+    pub bandwidth_urls: Vec<String>,
+}
+
+/// Settings for the free space history behind disk-fill forecasts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Record free space samples whenever disks are collected; off by default
+    /// so the tool writes nothing to disk unless asked to
+    pub enabled: bool,
+    /// History file (default: ~/.local/state/system-index/disk-history.json)
+    pub path: Option<PathBuf>,
+    /// Days of samples kept and fitted
+    pub window_days: u64,
+    /// `disk-full` is critical when a filesystem is forecast full within this many hours
+    pub critical_hours: u64,
+    /// `disk-full` warns when a filesystem is forecast full within this many days
+    pub warning_days: u64,
+}
+
+/// Thresholds of the `pressure` check
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PressureConfig {
+    /// Percent of the last minute memory or I/O stalled at which `pressure` warns
+    pub warning_percent: f64,
+    /// Percent of the last minute memory or I/O stalled at which `pressure` is critical
+    pub critical_percent: f64,
+}
+
+impl Default for PressureConfig {
+    fn default() -> Self {
+        Self {
+            warning_percent: PRESSURE_WARNING,
+            critical_percent: PRESSURE_CRITICAL,
+        }
+    }
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            window_days: 7,
+            critical_hours: DISK_FULL_CRITICAL.as_secs() / 3600,
+            warning_days: DISK_FULL_WARNING.as_secs() / 86400,
+        }
+    }
+}
+
+impl HistoryConfig {
+    /// History file to use; `None` when disabled or no location is known
+    pub fn file(&self) -> Option<PathBuf> {
+        if !self.enabled {
+            return None;
+        }
+        self.path.clone().or_else(DiskHistory::default_path)
+    }
+
+    pub fn window(&self) -> Duration {
+        Duration::from_secs(self.window_days * 24 * 60 * 60)
+    }
+
+    /// Forecast time until full below which a filesystem is critical
+    pub fn critical_within(&self) -> Duration {
+        Duration::from_secs(self.critical_hours * 60 * 60)
+    }
+
+    /// Forecast time until full below which a filesystem warns
+    pub fn warning_within(&self) -> Duration {
+        Duration::from_secs(self.warning_days * 24 * 60 * 60)
+    }
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        let options = CollectOptions::new();
+        Self {
+            online: false,
+            public_ip_timeout_secs: options.public_ip_timeout.as_secs(),
+            bandwidth_timeout_secs: options.bandwidth_timeout.as_secs(),
+            public_ip_resolvers: options.public_ip_resolvers,
+            bandwidth_urls: options.bandwidth_urls,
+        }
+    }
+}
+
+impl Config {
+    /// Load configuration from `path`, or from the default location
+    ///
+    /// An explicitly given file must exist; a missing default file yields the
+    /// default configuration.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        match path {
+            Some(path) => Self::from_file(path),
+            None => match Self::default_path() {
+                Some(path) if path.exists() => Self::from_file(&path),
+                _ => Ok(Self::default()),
+            },
+        }
+    }
+
+    /// Parse a configuration file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("failed to read {}: {}", path.display(), e))?;
+        let config = toml::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("invalid config {}: {}", path.display(), e))?;
+        Ok(config)
+    }
+
+    /// Default config location: `$XDG_CONFIG_HOME/system-index/config.toml`,
+    /// falling back to `~/.config/system-index/config.toml`
+    pub fn default_path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(base.join("system-index").join(CONFIG_FILE_NAME))
+    }
+
+    /// Apply the configured probe endpoints and timeouts to collection options
+    pub fn apply(&self, options: CollectOptions) -> CollectOptions {
+        options
+            .public_ip_timeout(Duration::from_secs(self.network.public_ip_timeout_secs))
+            .bandwidth_timeout(Duration::from_secs(self.network.bandwidth_timeout_secs))
+            .public_ip_resolvers(self.network.public_ip_resolvers.clone())
+            .bandwidth_urls(self.network.bandwidth_urls.clone())
+    }
+
+    /// Settings for `check`
+    pub fn check_settings(&self) -> CheckSettings {
+        CheckSettings {
+            disk_history: self.history.enabled,
+            disk_full_critical: self.history.critical_within(),
+            disk_full_warning: self.history.warning_within(),
+            pressure_warning: self.pressure.warning_percent,
+            pressure_critical: self.pressure.critical_percent,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_is_offline() {
+        let config = Config::default();
+        assert!(!config.network.online);
+        assert_eq!(config.network.public_ip_timeout_secs, 5);
+        assert_eq!(config.network.bandwidth_timeout_secs, 10);
+    }
+
+    #[test]
+    fn test_parse_partial_config() {
+        let config: Config = toml::from_str("[network]\nonline = true\n").unwrap();
+        assert!(config.network.online);
+        assert_eq!(config.network.bandwidth_timeout_secs, 10);
+    }
+
+    #[test]
+    fn test_apply_timeouts() {
+        let config: Config = toml::from_str("[network]\npublic_ip_timeout_secs = 2\n").unwrap();
+        let options = config.apply(CollectOptions::new());
+        assert_eq!(options.public_ip_timeout, Duration::from_secs(2));
+        assert_eq!(options.bandwidth_timeout, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_custom_endpoints() {
+        let config: Config = toml::from_str(
+            r#"
+            [network]
+            bandwidth_urls = ["http://mirror.internal/1mb.bin"]
+
+            [[network.public_ip_resolvers]]
+            type = "http"
+            url = "http://echo.internal/"
+            parser = { format = "json", field = "remote_addr" }
+
+            [[network.public_ip_resolvers]]
+            type = "dns"
+            server = "resolver1.opendns.com:53"
+            query = "myip.opendns.com"
+            "#,
+        )
+        .unwrap();
+
+        let options = config.apply(CollectOptions::new());
+        assert_eq!(options.public_ip_resolvers.len(), 2);
+        assert_eq!(
+            options.bandwidth_urls,
+            vec!["http://mirror.internal/1mb.bin".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_history_config() {
+        let config = Config::default();
+        assert!(!config.history.enabled);
+        assert!(config.history.file().is_none());
+        assert_eq!(config.history.window(), Duration::from_secs(7 * 86400));
+
+        let config: Config = toml::from_str(
+            "[history]\nenabled = true\npath = \"/var/lib/system-index/history.json\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.history.file(),
+            Some(PathBuf::from("/var/lib/system-index/history.json"))
+        );
+
+        let config: Config = toml::from_str("[history]\nenabled = false\n").unwrap();
+        assert!(config.history.file().is_none());
+
+        assert_eq!(Config::default().check_settings(), CheckSettings::default());
+        let config: Config = toml::from_str("[history]\ncritical_hours = 6\n").unwrap();
+        let settings = config.check_settings();
+        assert_eq!(settings.disk_full_critical, Duration::from_secs(6 * 3600));
+        assert_eq!(settings.disk_full_warning, DISK_FULL_WARNING);
+    }
+
+    #[test]
+    fn test_pressure_config() {
+        let config: Config = toml::from_str("[pressure]\nwarning_percent = 5.0\n").unwrap();
+        let settings = config.check_settings();
+        assert_eq!(settings.pressure_warning, 5.0);
+        assert_eq!(settings.pressure_critical, PRESSURE_CRITICAL);
+    }
+
+    #[test]
+    fn test_missing_explicit_file_is_error() {
+        assert!(Config::load(Some(Path::new("/nonexistent/system-index.toml"))).is_err());
+    }
+}
@@ -0,0 +1,487 @@
+use crate::models::{CollectOptions, DiskInfo, MdArray, Pressure, Section, SystemInfo};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Default forecast time until full below which `disk-full` is critical
+pub const DISK_FULL_CRITICAL: Duration = Duration::from_secs(48 * 60 * 60);
+
+/// Default forecast time until full below which `disk-full` warns
+pub const DISK_FULL_WARNING: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Default memory or I/O stall share over the last minute at which `pressure` warns, in percent
+pub const PRESSURE_WARNING: f64 = 10.0;
+
+/// Default memory or I/O stall share over the last minute at which `pressure` is critical, in percent
+pub const PRESSURE_CRITICAL: f64 = 25.0;
+
+/// Outcome of a check, ordered from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Warning,
+    Critical,
+}
+
+/// A condition evaluated by `system-index check`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Check {
+    /// md RAID arrays with missing or failed members
+    RaidDegraded,
+    /// Filesystems forecast to fill up soon, from recorded free space history
+    DiskFull,
+    /// Tasks stalled waiting for memory or I/O, from Pressure Stall Information;
+    /// only run when named, as stalls come and go on busy but healthy machines
+    Pressure,
+}
+
+/// Settings the checks are evaluated with
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckSettings {
+    /// Whether free space history is recorded; `disk-full` has no forecasts without it
+    pub disk_history: bool,
+    /// Forecast time until full below which `disk-full` is critical
+    pub disk_full_critical: Duration,
+    /// Forecast time until full below which `disk-full` warns
+    pub disk_full_warning: Duration,
+    /// `some avg60` percentage at which `pressure` warns
+    pub pressure_warning: f64,
+    /// `some avg60` percentage at which `pressure` is critical
+    pub pressure_critical: f64,
+}
+
+impl Default for CheckSettings {
+    fn default() -> Self {
+        Self {
+            disk_history: false,
+            disk_full_critical: DISK_FULL_CRITICAL,
+            disk_full_warning: DISK_FULL_WARNING,
+            pressure_warning: PRESSURE_WARNING,
+            pressure_critical: PRESSURE_CRITICAL,
+        }
+    }
+}
+
+/// Result of one check for one subject
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub check: Check,
+    pub status: Status,
+    /// What the result is about, e.g. `md0`; `None` for system-wide results
+    pub subject: Option<String>,
+    pub message: String,
+}
+
+/// Results of a set of checks and the worst status among them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub status: Status,
+    pub results: Vec<CheckResult>,
+}
+
+impl Status {
+    /// Process exit code following the Nagios plugin convention
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Status::Ok => 0,
+            Status::Warning => 1,
+            Status::Critical => 2,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Status::Ok => "OK",
+            Status::Warning => "WARNING",
+            Status::Critical => "CRITICAL",
+        }
+    }
+}
+
+impl Check {
+    /// Every check, in evaluation order
+    pub const ALL: [Check; 3] = [Check::RaidDegraded, Check::DiskFull, Check::Pressure];
+
+    /// Checks run when none are named
+    pub const DEFAULT: [Check; 2] = [Check::RaidDegraded, Check::DiskFull];
+
+    /// Name used on the command line and in reports
+    pub fn name(self) -> &'static str {
+        match self {
+            Check::RaidDegraded => "raid-degraded",
+            Check::DiskFull => "disk-full",
+            Check::Pressure => "pressure",
+        }
+    }
+
+    /// Sections that must be collected to evaluate this check
+    pub fn sections(self) -> &'static [Section] {
+        match self {
+            Check::RaidDegraded | Check::DiskFull => &[Section::Disks],
+            Check::Pressure => &[Section::Pressure],
+        }
+    }
+
+    /// Evaluate against a snapshot; always yields at least one result
+    pub fn evaluate(self, info: &SystemInfo, settings: &CheckSettings) -> Vec<CheckResult> {
+        match self {
+            Check::RaidDegraded => self.evaluate_raid(&info.raid_arrays),
+            Check::DiskFull => self.evaluate_disk_full(&info.disks, settings),
+            Check::Pressure => self.evaluate_pressure(&info.pressure, settings),
+        }
+    }
+
+    /// Memory and I/O only: some CPU stall is normal on any busy machine
+    fn evaluate_pressure(self, pressure: &Pressure, settings: &CheckSettings) -> Vec<CheckResult> {
+        let results: Vec<CheckResult> = [("memory", pressure.memory), ("io", pressure.io)]
+            .into_iter()
+            .filter_map(|(name, resource)| {
+                let some = resource?.some;
+                let status = if some.avg60 >= settings.pressure_critical {
+                    Status::Critical
+                } else if some.avg60 >= settings.pressure_warning {
+                    Status::Warning
+                } else {
+                    Status::Ok
+                };
+                let message = format!(
+                    "{:.1}% of the last minute stalled (some avg10 {:.1}%, avg300 {:.1}%)",
+                    some.avg60, some.avg10, some.avg300
+                );
+                Some(self.result(status, Some(name.to_string()), message))
+            })
+            .collect();
+
+        if results.is_empty() {
+            return vec![self.result(
+                Status::Ok,
+                None,
+                "Pressure Stall Information is not available".to_string(),
+            )];
+        }
+        results
+    }
+
+    fn evaluate_disk_full(self, disks: &[DiskInfo], settings: &CheckSettings) -> Vec<CheckResult> {
+        let results: Vec<CheckResult> = disks
+            .iter()
+            .filter_map(|disk| {
+                let forecast = disk.forecast.as_ref()?;
+                let status = if forecast.full_within(settings.disk_full_critical) {
+                    Status::Critical
+                } else if forecast.full_within(settings.disk_full_warning) {
+                    Status::Warning
+                } else {
+                    Status::Ok
+                };
+                Some(self.result(status, Some(disk.mount_point.clone()), forecast.summary()))
+            })
+            .collect();
+
+        if results.is_empty() {
+            let message = if settings.disk_history {
+                "no forecasts yet, free space history is still being recorded"
+            } else {
+                "no forecasts, free space history is disabled (see [history] in the config)"
+            };
+            return vec![self.result(Status::Ok, None, message.to_string())];
+        }
+        results
+    }
+
+    fn evaluate_raid(self, arrays: &[MdArray]) -> Vec<CheckResult> {
+        if arrays.is_empty() {
+            return vec![self.result(Status::Ok, None, "no md arrays".to_string())];
+        }
+
+        arrays
+            .iter()
+            .map(|array| {
+                let level = array.level.as_deref().unwrap_or("array");
+                let mut message = match (array.raid_devices, array.active_devices) {
+                    (Some(total), Some(up)) => {
+                        format!(
+                            "{} {}, {}/{} devices in sync",
+                            level,
+                            array.state_label(),
+                            up,
+                            total
+                        )
+                    }
+                    _ => format!("{} {}", level, array.state_label()),
+                };
+                let failed: Vec<&str> = array
+                    .failed_members()
+                    .iter()
+                    .map(|member| member.device.as_str())
+                    .collect();
+                if !failed.is_empty() {
+                    message.push_str(&format!(", failed {}", failed.join(", ")));
+                }
+                if let Some(sync) = &array.sync {
+                    message.push_str(&format!("; {}", sync.summary()));
+                }
+
+                let status = if array.is_degraded() {
+                    Status::Critical
+                } else if !array.active {
+                    Status::Warning
+                } else {
+                    Status::Ok
+                };
+                self.result(status, Some(array.name.clone()), message)
+            })
+            .collect()
+    }
+
+    fn result(self, status: Status, subject: Option<String>, message: String) -> CheckResult {
+        CheckResult {
+            check: self,
+            status,
+            subject,
+            message,
+        }
+    }
+}
+
+impl CheckResult {
+    /// One-line summary, e.g. `CRITICAL raid-degraded md1: raid5 DEGRADED, 3/4 devices in sync`
+    pub fn summary(&self) -> String {
+        match &self.subject {
+            Some(subject) => format!(
+                "{} {} {}: {}",
+                self.status.label(),
+                self.check.name(),
+                subject,
+                self.message
+            ),
+            None => format!(
+                "{} {}: {}",
+                self.status.label(),
+                self.check.name(),
+                self.message
+            ),
+        }
+    }
+}
+
+impl HealthReport {
+    /// Evaluate `checks` against a snapshot; the default checks when `checks` is empty
+    pub fn evaluate(info: &SystemInfo, checks: &[Check], settings: &CheckSettings) -> Self {
+        let checks = if checks.is_empty() {
+            &Check::DEFAULT[..]
+        } else {
+            checks
+        };
+        let results: Vec<CheckResult> = checks
+            .iter()
+            .flat_map(|check| check.evaluate(info, settings))
+            .collect();
+        Self {
+            status: results
+                .iter()
+                .map(|result| result.status)
+                .max()
+                .unwrap_or(Status::Ok),
+            results,
+        }
+    }
+
+    /// Collection options gathering what `checks` need; the default checks when empty
+    pub fn collect_options(checks: &[Check]) -> CollectOptions {
+        let checks = if checks.is_empty() {
+            &Check::DEFAULT[..]
+        } else {
+            checks
+        };
+        CollectOptions::empty().sections(
+            checks
+                .iter()
+                .flat_map(|check| check.sections().iter().copied()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixture_path;
+    use crate::models::DiskForecast;
+
+    const MDSTAT: &str = "\
+Personalities : [raid1] [raid5]
+md1 : active raid5 sdf1[4] sde1[2] sdd1[1](F) sdc1[0]
+      5860270080 blocks super 1.2 level 5, 512k chunk, algorithm 2 [4/3] [U_UU]
+      [=>...................]  recovery =  8.5% (166275584/1953423360) finish=151.2min speed=196888K/sec
+
+md0 : active raid1 sdb1[1] sda1[0]
+      1953381376 blocks super 1.2 [2/2] [UU]
+
+md127 : inactive sdk[1](S) sdj[0](S)
+      3906764976 blocks super 1.2
+
+unused devices: <none>
+";
+
+    fn info_with_arrays(mdstat: &str) -> SystemInfo {
+        SystemInfo {
+            raid_arrays: MdArray::parse(mdstat),
+            ..SystemInfo::default()
+        }
+    }
+
+    #[test]
+    fn test_raid_degraded() {
+        let report = HealthReport::evaluate(
+            &info_with_arrays(MDSTAT),
+            &[Check::RaidDegraded],
+            &CheckSettings::default(),
+        );
+        assert_eq!(report.status, Status::Critical);
+        assert_eq!(report.status.exit_code(), 2);
+
+        let statuses: Vec<Status> = report.results.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, [Status::Critical, Status::Ok, Status::Warning]);
+        assert_eq!(
+            report.results[0].summary(),
+            "CRITICAL raid-degraded md1: raid5 DEGRADED, 3/4 devices in sync, failed sdd1; \
+             recovery 8.5%, 192.27 MB/s, ETA 2h 31m 12s"
+        );
+        assert_eq!(
+            report.results[1].summary(),
+            "OK raid-degraded md0: raid1 clean, 2/2 devices in sync"
+        );
+    }
+
+    #[test]
+    fn test_disk_full() {
+        const HOUR: u64 = 3600;
+        let disk = |mount_point: &str, seconds_until_full: Option<u64>| DiskInfo {
+            name: "/dev/vda".to_string(),
+            mount_point: mount_point.to_string(),
+            total_space: 100,
+            available_space: 10,
+            file_system: "ext4".to_string(),
+            inodes: None,
+            dev: None,
+            source: None,
+            mount_options: Vec::new(),
+            forecast: seconds_until_full.map(|secs| DiskForecast {
+                fill_rate_bytes_per_sec: 1.0,
+                seconds_until_full: Some(secs),
+                samples: 12,
+                span_secs: 6 * HOUR,
+            }),
+        };
+        let info = SystemInfo {
+            disks: vec![
+                disk("/", Some(30 * HOUR)),
+                disk("/home", Some(100 * HOUR)),
+                disk("/srv", Some(1000 * HOUR)),
+                disk("/boot", None),
+            ],
+            ..SystemInfo::default()
+        };
+
+        let settings = CheckSettings {
+            disk_history: true,
+            ..CheckSettings::default()
+        };
+        let report = HealthReport::evaluate(&info, &[Check::DiskFull], &settings);
+        assert_eq!(report.status, Status::Critical);
+        let statuses: Vec<Status> = report.results.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, [Status::Critical, Status::Warning, Status::Ok]);
+        assert_eq!(
+            report.results[0].summary(),
+            "CRITICAL disk-full /: filling at 84.38 KB/day, full in 1d 6h 0m 0s \
+             (12 samples over 6h 0m 0s)"
+        );
+
+        // Tighter thresholds
+        let strict = CheckSettings {
+            disk_full_critical: Duration::from_secs(12 * HOUR),
+            disk_full_warning: Duration::from_secs(50 * HOUR),
+            ..settings.clone()
+        };
+        let report = HealthReport::evaluate(&info, &[Check::DiskFull], &strict);
+        let statuses: Vec<Status> = report.results.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, [Status::Warning, Status::Ok, Status::Ok]);
+
+        let empty = SystemInfo::default();
+        let report = HealthReport::evaluate(&empty, &[Check::DiskFull], &settings);
+        assert_eq!(
+            report.results[0].summary(),
+            "OK disk-full: no forecasts yet, free space history is still being recorded"
+        );
+        let report = HealthReport::evaluate(&empty, &[Check::DiskFull], &CheckSettings::default());
+        assert_eq!(
+            report.results[0].summary(),
+            "OK disk-full: no forecasts, free space history is disabled (see [history] in the config)"
+        );
+    }
+
+    #[test]
+    fn test_no_arrays_is_ok() {
+        let report = HealthReport::evaluate(
+            &SystemInfo::default(),
+            &[Check::RaidDegraded],
+            &CheckSettings::default(),
+        );
+        assert_eq!(report.status, Status::Ok);
+        assert_eq!(report.results.len(), 1);
+        assert_eq!(
+            report.results[0].summary(),
+            "OK raid-degraded: no md arrays"
+        );
+
+        let report = HealthReport::evaluate(&SystemInfo::default(), &[], &CheckSettings::default());
+        assert_eq!(report.status, Status::Ok);
+        assert_eq!(report.results.len(), 2);
+        assert!(report.results.iter().all(|r| r.check != Check::Pressure));
+
+        let report = HealthReport::evaluate(
+            &SystemInfo::default(),
+            &[Check::Pressure],
+            &CheckSettings::default(),
+        );
+        assert_eq!(
+            report.results[0].summary(),
+            "OK pressure: Pressure Stall Information is not available"
+        );
+    }
+
+    #[test]
+    fn test_pressure() {
+        let info = SystemInfo {
+            pressure: Pressure::read(&fixture_path("proc/pressure")),
+            ..SystemInfo::default()
+        };
+        let report = HealthReport::evaluate(&info, &[Check::Pressure], &CheckSettings::default());
+        assert_eq!(report.status, Status::Critical);
+        let statuses: Vec<Status> = report.results.iter().map(|r| r.status).collect();
+        assert_eq!(statuses, [Status::Ok, Status::Critical]);
+        assert_eq!(
+            report.results[1].summary(),
+            "CRITICAL pressure io: 25.0% of the last minute stalled (some avg10 30.0%, avg300 18.0%)"
+        );
+
+        let lenient = CheckSettings {
+            pressure_warning: 20.0,
+            pressure_critical: 50.0,
+            ..CheckSettings::default()
+        };
+        let report = HealthReport::evaluate(&info, &[Check::Pressure], &lenient);
+        assert_eq!(report.status, Status::Warning);
+    }
+
+    #[test]
+    fn test_collect_options() {
+        let options = HealthReport::collect_options(&[Check::RaidDegraded]);
+        assert_eq!(options.sections, vec![Section::Disks]);
+        assert_eq!(
+            HealthReport::collect_options(&[]).sections,
+            vec![Section::Disks]
+        );
+    }
+}
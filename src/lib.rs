@@ -3,8 +3,14 @@
 //! A CLI and TUI tool for displaying comprehensive system information including
 //! CPU, memory, disk, network, and operating system details.
 
+pub mod config;
+pub mod health;
+pub mod metrics;
 pub mod models;
+pub mod output;
+pub mod probes;
 pub mod tui;
+pub mod usage;
 
 pub use models::*;
 
@@ -1,5 +1,23 @@
 use clap::{Parser, Subcommand};
-use system_index::{models::SystemInfo, tui::App};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+use system_index::{
+    config::{Config, HistoryConfig},
+    health::{Check, HealthReport, Status},
+    metrics::MetricsServer,
+    models::{
+        BlockDevice, CollectOptions, DiskFilter, DiskHistory, DiskIoStats, Section, SystemInfo,
+        VulnerabilityState, DISK_SAMPLE_INTERVAL,
+    },
+    output::{
+        CpuFeaturesReport, CpuReport, DisksReport, MemoryReport, NetworkReport, OutputFormat,
+        OverviewReport,
+    },
+    tui::App,
+    usage::{self, FileUsage, ScanOptions, UsageReport},
+};
 
 /// A CLI and TUI tool for displaying system information
 #[derive(Parser)]
@@ -9,6 +27,50 @@ use system_index::{models::SystemInfo, tui::App};
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Output format for information commands
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Path to a config file (default: ~/.config/system-index/config.toml)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Allow probes that contact external services (public IP, bandwidth)
+    #[arg(long, global = true, conflicts_with = "offline")]
+    online: bool,
+
+    /// Never make outbound connections, even if the config enables them
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// Only show filesystems of these types (repeatable or comma-separated)
+    #[arg(long = "fs-type", global = true, value_delimiter = ',')]
+    fs_types: Vec<String>,
+
+    /// Hide filesystems of these types (repeatable or comma-separated)
+    #[arg(long = "exclude-fs-type", global = true, value_delimiter = ',')]
+    exclude_fs_types: Vec<String>,
+
+    /// Only show filesystems mounted at paths matching these globs, e.g. '/mnt/*'
+    #[arg(long = "mount", global = true)]
+    mounts: Vec<String>,
+
+    /// Include pseudo filesystems such as tmpfs, overlay and squashfs
+    #[arg(long, global = true)]
+    all_filesystems: bool,
+}
+
+impl Cli {
+    /// Filesystem filter selected on the command line
+    fn disk_filter(&self) -> DiskFilter {
+        DiskFilter {
+            fs_types: self.fs_types.clone(),
+            exclude_fs_types: self.exclude_fs_types.clone(),
+            mounts: self.mounts.clone(),
+            include_pseudo: self.all_filesystems,
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -18,52 +80,220 @@ enum Commands {
     /// Display system overview
     Overview,
     /// Display CPU information
-    Cpu,
+    Cpu {
+        /// Milliseconds between the two samples used for usage and time breakdown
+        #[arg(long, default_value_t = 200)]
+        interval_ms: u64,
+        /// Show instruction set flags, microcode and vulnerability mitigations
+        #[arg(long)]
+        features: bool,
+    },
     /// Display memory information
     Memory,
     /// Display disk information
-    Disks,
+    Disks {
+        /// Milliseconds over which I/O rates are measured
+        #[arg(long, default_value_t = 1000)]
+        interval_ms: u64,
+    },
     /// Display network information
-    Network,
+    Network {
+        /// Milliseconds over which throughput rates are measured
+        #[arg(long, default_value_t = 1000)]
+        interval_ms: u64,
+    },
     /// Display all system information
     All,
+    /// Evaluate health checks; exits 0 when OK, 1 on warnings, 2 on critical findings
+    Check {
+        /// Checks to run (default: raid-degraded and disk-full; pressure must be named)
+        #[arg(value_enum)]
+        checks: Vec<Check>,
+    },
+    /// Show which directories and files take up space below a path
+    Usage {
+        /// Directory to scan
+        path: PathBuf,
+        /// Number of directories and files to list
+        #[arg(long, default_value_t = usage::DEFAULT_TOP)]
+        top: usize,
+        /// Descend into other filesystems mounted below the path
+        #[arg(long)]
+        cross_filesystems: bool,
+    },
+    /// Serve Prometheus/OpenMetrics metrics over HTTP on /metrics
+    ServeMetrics {
+        /// Address to listen on
+        #[arg(long, default_value = "0.0.0.0:9110")]
+        listen: String,
+    },
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
+    let config = Config::load(cli.config.as_deref())?;
+    let online = !cli.offline && (cli.online || config.network.online);
+
+    let disk_filter = cli.disk_filter();
 
-    match cli.command {
+    let command = match cli.command {
         Some(Commands::Tui) | None => {
             // Default to TUI mode
-            let mut app = App::new();
+            let options = config
+                .apply(
+                    CollectOptions::new()
+                        .network_probes(online)
+                        .disk_sample_interval(DISK_SAMPLE_INTERVAL),
+                )
+                .disk_filter(disk_filter.clone());
+            let mut app = App::with_options(options);
+            if let Some(path) = config.history.file() {
+                app = app.with_disk_history(
+                    path,
+                    config.history.window(),
+                    config.history.critical_within(),
+                );
+            }
             app.run()?;
+            return Ok(());
         }
-        Some(Commands::Overview) => {
-            print_overview();
-        }
-        Some(Commands::Cpu) => {
-            print_cpu_info();
+        Some(Commands::ServeMetrics { listen }) => {
+            let server = MetricsServer::bind(&listen, disk_filter)?;
+            if let Some(addr) = server.local_addr() {
+                eprintln!("Serving metrics on http://{}/metrics", addr);
+            }
+            server.run()?;
+            return Ok(());
         }
-        Some(Commands::Memory) => {
-            print_memory_info();
+        Some(Commands::Check { checks }) => {
+            let options = config
+                .apply(HealthReport::collect_options(&checks))
+                .disk_filter(disk_filter.clone());
+            let mut info = SystemInfo::collect_with(&options);
+            forecast_disks(&config, &mut info);
+            let report = HealthReport::evaluate(&info, &checks, &config.check_settings());
+            if cli.format == OutputFormat::Text {
+                print_health(&report);
+            } else {
+                emit(&report, cli.format)?;
+            }
+            std::process::exit(report.status.exit_code());
         }
-        Some(Commands::Disks) => {
-            print_disk_info();
+        Some(Commands::Usage {
+            path,
+            top,
+            cross_filesystems,
+        }) => {
+            let options = ScanOptions {
+                top,
+                cross_filesystems,
+                ..ScanOptions::default()
+            };
+            let cancel = AtomicBool::new(false);
+            let scan = usage::scan(&path, &options, &cancel)?
+                .ok_or_else(|| anyhow::anyhow!("scan cancelled"))?;
+            let report = UsageReport::from_scan(&scan, top);
+            if cli.format == OutputFormat::Text {
+                print_usage(&report);
+            } else {
+                emit(&report, cli.format)?;
+            }
+            return Ok(());
         }
-        Some(Commands::Network) => {
-            print_network_info();
-        }
-        Some(Commands::All) => {
-            print_all_info();
+        Some(command) => command,
+    };
+
+    let options = config
+        .apply(collect_options(&command, online))
+        .disk_filter(disk_filter);
+    let mut info = SystemInfo::collect_with(&options);
+    if options.includes(Section::Disks) {
+        forecast_disks(&config, &mut info);
+    }
+
+    if cli.format == OutputFormat::Text {
+        match command {
+            Commands::Overview => print_overview(&info),
+            Commands::Cpu { features: true, .. } => print_cpu_features(&info),
+            Commands::Cpu { .. } => print_cpu_info(&info),
+            Commands::Memory => print_memory_info(&info),
+            Commands::Disks { .. } => print_disk_info(&info, &config.history),
+            Commands::Network { .. } => print_network_info(&info),
+            Commands::All => print_all_info(&info, &config.history),
+            Commands::Tui
+            | Commands::Check { .. }
+            | Commands::Usage { .. }
+            | Commands::ServeMetrics { .. } => unreachable!("handled above"),
         }
+        return Ok(());
+    }
+
+    match command {
+        Commands::Overview => emit(&OverviewReport::from(&info), cli.format)?,
+        Commands::Cpu { features: true, .. } => emit(&CpuFeaturesReport::from(&info), cli.format)?,
+        Commands::Cpu { .. } => emit(&CpuReport::from(&info), cli.format)?,
+        Commands::Memory => emit(&MemoryReport::from(&info), cli.format)?,
+        Commands::Disks { .. } => emit(&DisksReport::from(&info), cli.format)?,
+        Commands::Network { .. } => emit(&NetworkReport::from(&info), cli.format)?,
+        Commands::All => emit(&info, cli.format)?,
+        Commands::Tui
+        | Commands::Check { .. }
+        | Commands::Usage { .. }
+        | Commands::ServeMetrics { .. } => unreachable!("handled above"),
     }
 
     Ok(())
 }
 
-fn print_overview() {
-    let info = SystemInfo::collect();
+/// Sections each information command needs to display
+///
+/// External probes only run when `online` is set.
+fn collect_options(command: &Commands, online: bool) -> CollectOptions {
+    match command {
+        // Overview shows neither network throughput nor disk I/O rates
+        Commands::Overview => CollectOptions::new()
+            .public_ip(online)
+            .disk_sample_interval(Duration::ZERO)
+            .network_sample_interval(Duration::ZERO),
+        Commands::Cpu { interval_ms, .. } => CollectOptions::empty()
+            .section(Section::Cpu)
+            .cpu_sample_interval(Duration::from_millis(*interval_ms)),
+        Commands::Memory => CollectOptions::empty().section(Section::Memory),
+        Commands::Disks { interval_ms } => CollectOptions::empty()
+            .section(Section::Disks)
+            .disk_sample_interval(Duration::from_millis(*interval_ms)),
+        Commands::Network { interval_ms } => CollectOptions::empty()
+            .sections([Section::Networks, Section::NetworkDetails])
+            .network_sample_interval(Duration::from_millis(*interval_ms))
+            .network_probes(online),
+        Commands::All
+        | Commands::Tui
+        | Commands::Check { .. }
+        | Commands::Usage { .. }
+        | Commands::ServeMetrics { .. } => CollectOptions::new().network_probes(online),
+    }
+}
 
+/// Record free space history and attach disk-fill forecasts
+///
+/// History problems are reported but never fail the command.
+fn forecast_disks(config: &Config, info: &mut SystemInfo) {
+    let Some(path) = config.history.file() else {
+        return;
+    };
+    if let Err(err) = DiskHistory::update(&path, &mut info.disks, config.history.window()) {
+        eprintln!("Warning: disk history not updated: {:#}", err);
+    }
+}
+
+/// Print a report in a structured output format
+fn emit<T: Serialize>(report: &T, format: OutputFormat) -> Result<(), Box<dyn std::error::Error>> {
+    let output = format.render(report)?;
+    println!("{}", output.trim_end());
+    Ok(())
+}
+
+fn print_overview(info: &SystemInfo) {
     println!("╔═══════════════════════════════════════════════════════╗");
     println!("║              SYSTEM OVERVIEW                          ║");
     println!("╚═══════════════════════════════════════════════════════╝");
@@ -75,9 +305,15 @@ fn print_overview() {
         "⏰ System Uptime:    {}",
         SystemInfo::format_uptime(info.uptime)
     );
+    println!("📦 Environment:      {}", info.environment.describe());
     println!();
     println!("⚙️  CPU:             {}", info.cpu_brand);
     println!("📊 CPU Cores:        {}", info.cpu_count);
+    println!("🔥 CPU Usage:        {:.2}%", info.cpu.global_usage);
+    println!(
+        "📉 Load Average:     {:.2}, {:.2}, {:.2}",
+        info.cpu.load_average.one, info.cpu.load_average.five, info.cpu.load_average.fifteen
+    );
     println!();
     println!(
         "💾 Total Memory:     {}",
@@ -88,34 +324,144 @@ fn print_overview() {
         SystemInfo::format_bytes(info.used_memory)
     );
     println!();
+
+    if let Some(cgroup) = &info.cgroup {
+        println!("═══ CGROUP LIMITS ({} {}) ═══", cgroup.version, cgroup.path);
+        for line in cgroup.summary_lines(info.cpu_count, info.total_memory) {
+            println!("{}", line);
+        }
+        println!();
+    }
     println!("💿 Mounted Disks:    {}", info.disks.len());
     println!("🌐 Network Interfaces: {}", info.networks.len());
     if let Some(local_ip) = &info.network_details.local_ip {
         println!("🏠 Local IP:         {}", local_ip);
     }
-    if let Some(public_ip) = &info.network_details.public_ip {
-        println!("🌍 Public IP:        {}", public_ip);
-    }
+    println!(
+        "🌍 Public IP:        {}",
+        info.network_details.public_ip_display()
+    );
     println!("📋 Running Processes: {}", info.processes_count);
-}
 
-fn print_cpu_info() {
-    let info = SystemInfo::collect();
+    if info.pressure.is_available() {
+        println!();
+        println!("═══ PRESSURE (avg10/avg60/avg300) ═══");
+        for (resource, pressure) in info.pressure.entries() {
+            println!("{:<8} {}", resource, pressure.summary());
+        }
+    }
+}
 
+fn print_cpu_info(info: &SystemInfo) {
     println!("╔═══════════════════════════════════════════════════════╗");
     println!("║              CPU INFORMATION                          ║");
     println!("╚═══════════════════════════════════════════════════════╝");
     println!();
     println!("⚙️  CPU Brand:       {}", info.cpu_brand);
     println!("📊 Number of Cores:  {}", info.cpu_count);
+    println!("🔥 Global Usage:     {:.2}%", info.cpu.global_usage);
+    println!(
+        "📉 Load Average:     {:.2} (1m), {:.2} (5m), {:.2} (15m)",
+        info.cpu.load_average.one, info.cpu.load_average.five, info.cpu.load_average.fifteen
+    );
+    println!();
+
+    if let Some(times) = &info.cpu.times {
+        println!("═══ CPU TIME BREAKDOWN ({} ms) ═══", times.interval_ms);
+        for (state, percent) in times.entries() {
+            println!("{:<12} {:>6.2}%", state, percent);
+        }
+        println!();
+    }
+
+    if let Some(topology) = &info.cpu.topology {
+        println!("═══ CPU TOPOLOGY ═══");
+        for line in topology.tree_lines() {
+            println!("{}", line);
+        }
+        println!();
+    }
+
+    if info.cpu.cores.is_empty() {
+        return;
+    }
+
+    println!("═══ PER-CORE USAGE ═══");
+    for core in &info.cpu.cores {
+        let limits = match (core.min_frequency_mhz, core.max_frequency_mhz) {
+            (Some(min), Some(max)) => format!(" (min {} / max {} MHz)", min, max),
+            _ => String::new(),
+        };
+        println!(
+            "{:<8} {:>6.2}%  {} MHz{}",
+            core.name, core.usage, core.frequency_mhz, limits
+        );
+    }
 }
 
-fn print_memory_info() {
-    let info = SystemInfo::collect();
+fn print_cpu_features(info: &SystemInfo) {
+    println!("╔═══════════════════════════════════════════════════════╗");
+    println!("║              CPU FEATURES                             ║");
+    println!("╚═══════════════════════════════════════════════════════╝");
+    println!();
+    println!("⚙️  CPU Brand:       {}", info.cpu_brand);
+
+    let Some(features) = &info.cpu.features else {
+        println!();
+        println!("CPU feature information is not available on this system.");
+        return;
+    };
+
+    println!(
+        "🔧 Microcode:        {}",
+        features.microcode.as_deref().unwrap_or("Unknown")
+    );
+    println!();
+
+    println!("═══ INSTRUCTION SETS ═══");
+    for (name, supported) in features.instruction_sets() {
+        println!("{} {}", if supported { "✅" } else { "❌" }, name);
+    }
+    println!();
+
+    if !features.vulnerabilities.is_empty() {
+        println!("═══ VULNERABILITIES ═══");
+        for vulnerability in &features.vulnerabilities {
+            let marker = match vulnerability.state {
+                VulnerabilityState::NotAffected => "✅",
+                VulnerabilityState::Mitigated => "🛡️ ",
+                VulnerabilityState::Vulnerable => "⚠️ ",
+                VulnerabilityState::Unknown => "❓",
+            };
+            println!(
+                "{} {:<28} {}",
+                marker, vulnerability.name, vulnerability.status
+            );
+        }
+        println!();
+    }
 
+    if !features.flags.is_empty() {
+        println!("═══ ALL FLAGS ({}) ═══", features.flags.len());
+        for row in features.flags.chunks(12) {
+            println!("{}", row.join(" "));
+        }
+    }
+}
+
+fn print_memory_info(info: &SystemInfo) {
+    let meminfo = info.memory.meminfo;
     let total_mem = info.total_memory;
-    let used_mem = info.used_memory;
-    let free_mem = total_mem - used_mem;
+    // Match the stacked bar when /proc/meminfo was read; otherwise sysinfo's
+    // figure, which leaves out everything counted as available
+    let used_mem = meminfo.map_or(info.used_memory, |m| m.used());
+    let used_label = if meminfo.is_some() {
+        "Used Memory:"
+    } else {
+        "Used (excl. available):"
+    };
+    // Without /proc/meminfo, "free" also counts reclaimable page cache
+    let free_mem = meminfo.map_or(total_mem.saturating_sub(used_mem), |m| m.free);
     let mem_usage_percent = if total_mem > 0 {
         used_mem as f64 / total_mem as f64 * 100.0
     } else {
@@ -138,12 +484,41 @@ fn print_memory_info() {
     println!("═══ RAM MEMORY ═══");
     println!("Total Memory:    {}", SystemInfo::format_bytes(total_mem));
     println!(
-        "Used Memory:     {} ({:.2}%)",
+        "{:<16} {} ({:.2}%)",
+        used_label,
         SystemInfo::format_bytes(used_mem),
         mem_usage_percent
     );
     println!("Free Memory:     {}", SystemInfo::format_bytes(free_mem));
+    if let Some(meminfo) = &meminfo {
+        println!(
+            "Available:       {}",
+            SystemInfo::format_bytes(meminfo.available)
+        );
+    }
     println!();
+
+    if let Some(meminfo) = &meminfo {
+        println!("═══ RAM BREAKDOWN ═══");
+        println!("[{}]", meminfo.stacked_bar(50));
+        println!("{}", meminfo.legend());
+        println!();
+        let rows = [
+            ("Buffers:", meminfo.buffers),
+            ("Page Cache:", meminfo.cached),
+            ("Shared:", meminfo.shared),
+            ("Dirty:", meminfo.dirty),
+            ("Writeback:", meminfo.writeback),
+            ("Slab (reclaim):", meminfo.slab_reclaimable),
+            ("Slab (unreclaim):", meminfo.slab_unreclaimable),
+            ("Committed:", meminfo.committed),
+            ("Commit Limit:", meminfo.commit_limit),
+        ];
+        for (label, bytes) in rows {
+            println!("{:<18}{}", label, SystemInfo::format_bytes(bytes));
+        }
+        println!();
+    }
     println!("═══ SWAP MEMORY ═══");
     println!("Total Swap:      {}", SystemInfo::format_bytes(total_swap));
     println!(
@@ -152,11 +527,13 @@ fn print_memory_info() {
         swap_usage_percent
     );
     println!("Free Swap:       {}", SystemInfo::format_bytes(free_swap));
-}
 
-fn print_disk_info() {
-    let info = SystemInfo::collect();
+    for line in info.memory.section_lines() {
+        println!("{}", line);
+    }
+}
 
+fn print_disk_info(info: &SystemInfo, history: &HistoryConfig) {
     println!("╔═══════════════════════════════════════════════════════╗");
     println!("║              DISK INFORMATION                         ║");
     println!("╚═══════════════════════════════════════════════════════╝");
@@ -179,6 +556,16 @@ fn print_disk_info() {
         println!("Name:           {}", disk.name);
         println!("Mount Point:    {}", disk.mount_point);
         println!("File System:    {}", disk.file_system);
+        if let Some(source) = &disk.source {
+            println!(
+                "Source:         {} ({})",
+                source,
+                disk.dev.as_deref().unwrap_or("?")
+            );
+        }
+        if !disk.mount_options.is_empty() {
+            println!("Options:        {}", disk.mount_options.join(","));
+        }
         println!(
             "Total Space:    {}",
             SystemInfo::format_bytes(disk.total_space)
@@ -192,13 +579,60 @@ fn print_disk_info() {
             "Available Space: {}",
             SystemInfo::format_bytes(disk.available_space)
         );
+        match &disk.inodes {
+            Some(inodes) => println!(
+                "Inodes:         {} / {} used, {} free ({:.2}%)",
+                inodes.used,
+                inodes.total,
+                inodes.free,
+                inodes.usage_percent()
+            ),
+            None => println!("Inodes:         n/a"),
+        }
+        if let Some(forecast) = &disk.forecast {
+            let marker = if forecast.full_within(history.critical_within()) {
+                "⚠️  "
+            } else {
+                ""
+            };
+            println!("Forecast:       {}{}", marker, forecast.summary());
+        }
+        println!();
+    }
+
+    if !info.block_devices.is_empty() {
+        println!("═══ BLOCK DEVICES ═══");
+        for line in BlockDevice::tree_lines(&info.block_devices) {
+            println!("{}", line);
+        }
         println!();
     }
-}
 
-fn print_network_info() {
-    let info = SystemInfo::collect();
+    if !info.raid_arrays.is_empty() {
+        println!("═══ SOFTWARE RAID ═══");
+        for array in &info.raid_arrays {
+            let marker = if array.is_degraded() { "⚠️  " } else { "" };
+            for (index, line) in array.summary_lines().iter().enumerate() {
+                if index == 0 {
+                    println!("{}{}", marker, line);
+                } else {
+                    println!("{}", line);
+                }
+            }
+        }
+        println!();
+    }
+
+    if !info.disk_io.is_empty() {
+        println!("═══ I/O ACTIVITY ═══");
+        for line in DiskIoStats::table_lines(&info.disk_io) {
+            println!("{}", line);
+        }
+        println!();
+    }
+}
 
+fn print_network_info(info: &SystemInfo) {
     println!("╔═══════════════════════════════════════════════════════╗");
     println!("║              NETWORK INFORMATION                      ║");
     println!("╚═══════════════════════════════════════════════════════╝");
@@ -212,17 +646,14 @@ fn print_network_info() {
         println!("🏠 Local IP:        Not available");
     }
 
-    if let Some(public_ip) = &info.network_details.public_ip {
-        println!("🌍 Public IP:       {}", public_ip);
-    } else {
-        println!("🌍 Public IP:       Not available");
-    }
-
-    if let Some(bandwidth) = info.network_details.bandwidth_mbps {
-        println!("⚡ Bandwidth:       {:.2} Mbps", bandwidth);
-    } else {
-        println!("⚡ Bandwidth:       Not available");
-    }
+    println!(
+        "🌍 Public IP:       {}",
+        info.network_details.public_ip_display()
+    );
+    println!(
+        "⚡ Bandwidth:       {}",
+        info.network_details.bandwidth_display()
+    );
     println!();
 
     // Display network interfaces
@@ -233,7 +664,35 @@ fn print_network_info() {
 
     println!("═══ NETWORK INTERFACES ═══");
     for (idx, network) in info.networks.iter().enumerate() {
-        println!("Interface {}: {}", idx + 1, network.interface_name);
+        let link = network.link.summary();
+        let marker = if network.rates.as_ref().is_some_and(|r| r.has_faults()) {
+            "⚠️  "
+        } else {
+            ""
+        };
+        if link.is_empty() {
+            println!(
+                "{}Interface {}: {}",
+                marker,
+                idx + 1,
+                network.interface_name
+            );
+        } else {
+            println!(
+                "{}Interface {}: {} ({})",
+                marker,
+                idx + 1,
+                network.interface_name,
+                link
+            );
+        }
+        if let Some(mac) = &network.link.mac_address {
+            println!("  MAC:            {}", mac);
+        }
+        for address in &network.addresses {
+            let family = if address.is_ipv4() { "IPv4" } else { "IPv6" };
+            println!("  {}:           {}", family, address);
+        }
         println!(
             "  Received:       {}",
             SystemInfo::format_bytes(network.received_bytes)
@@ -246,18 +705,90 @@ fn print_network_info() {
             "  Total:          {}",
             SystemInfo::format_bytes(network.received_bytes + network.transmitted_bytes)
         );
+        if let Some(rates) = &network.rates {
+            println!("  Rate:           {}", rates.summary());
+        }
+        if let Some(stats) = &network.stats {
+            for (label, value) in stats.entries() {
+                println!("  {:<16}{}", format!("{}:", label), value);
+            }
+        }
         println!();
     }
 }
 
-fn print_all_info() {
-    print_overview();
+fn print_health(report: &HealthReport) {
+    println!("╔═══════════════════════════════════════════════════════╗");
+    println!("║              HEALTH CHECK                             ║");
+    println!("╚═══════════════════════════════════════════════════════╝");
+    println!();
+
+    for result in &report.results {
+        println!("{} {}", status_icon(result.status), result.summary());
+    }
+    println!();
+    println!(
+        "{} Overall: {}",
+        status_icon(report.status),
+        report.status.label()
+    );
+}
+
+fn print_usage(report: &UsageReport) {
+    println!("╔═══════════════════════════════════════════════════════╗");
+    println!("║              DISK USAGE                               ║");
+    println!("╚═══════════════════════════════════════════════════════╝");
+    println!();
+    println!("📂 Path:            {}", report.path.display());
+    println!(
+        "💾 Total Size:      {}",
+        SystemInfo::format_bytes(report.size_bytes)
+    );
+    println!(
+        "📄 Files:           {} in {} directories",
+        report.file_count, report.dir_count
+    );
+    println!("⏱️  Scan Time:      {} ms", report.elapsed_ms);
+    if report.unreadable > 0 {
+        println!(
+            "⚠️  Unreadable:      {} directories (sizes are a lower bound)",
+            report.unreadable
+        );
+    }
+    println!();
+
+    let sections = [
+        ("LARGEST DIRECTORIES", &report.largest_directories),
+        ("LARGEST FILES", &report.largest_files),
+    ];
+    for (title, entries) in sections {
+        if entries.is_empty() {
+            continue;
+        }
+        println!("═══ {} ═══", title);
+        for line in FileUsage::table_lines(entries, report.size_bytes, &report.path) {
+            println!("{}", line);
+        }
+        println!();
+    }
+}
+
+fn status_icon(status: Status) -> &'static str {
+    match status {
+        Status::Ok => "✅",
+        Status::Warning => "⚠️ ",
+        Status::Critical => "❌",
+    }
+}
+
+fn print_all_info(info: &SystemInfo, history: &HistoryConfig) {
+    print_overview(info);
     println!();
-    print_cpu_info();
+    print_cpu_info(info);
     println!();
-    print_memory_info();
+    print_memory_info(info);
     println!();
-    print_disk_info();
+    print_disk_info(info, history);
     println!();
-    print_network_info();
+    print_network_info(info);
 }
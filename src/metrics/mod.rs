@@ -0,0 +1,493 @@
+use crate::models::{CollectOptions, DiskFilter, Section, SystemInfo};
+use crate::Result;
+use std::fmt::Write as _;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+/// Prefix applied to every exported metric name
+const METRIC_PREFIX: &str = "system_index";
+
+/// Content type of the Prometheus text exposition format
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Content type of the OpenMetrics text format
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Exposition format served on `/metrics`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsFormat {
+    Prometheus,
+    OpenMetrics,
+}
+
+impl MetricsFormat {
+    /// Pick a format from the request's `Accept` header
+    pub fn negotiate(accept: Option<&str>) -> Self {
+        match accept {
+            Some(accept) if accept.contains("application/openmetrics-text") => {
+                MetricsFormat::OpenMetrics
+            }
+            _ => MetricsFormat::Prometheus,
+        }
+    }
+
+    /// HTTP `Content-Type` for this format
+    pub fn content_type(self) -> &'static str {
+        match self {
+            MetricsFormat::Prometheus => PROMETHEUS_CONTENT_TYPE,
+            MetricsFormat::OpenMetrics => OPENMETRICS_CONTENT_TYPE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MetricKind {
+    Gauge,
+    Counter,
+}
+
+/// A single metric family with its samples
+struct MetricFamily {
+    name: &'static str,
+    help: &'static str,
+    kind: MetricKind,
+    unit: Option<&'static str>,
+    samples: Vec<Sample>,
+}
+
+struct Sample {
+    labels: Vec<(&'static str, String)>,
+    value: f64,
+}
+
+impl MetricFamily {
+    fn new(name: &'static str, help: &'static str, kind: MetricKind) -> Self {
+        Self {
+            name,
+            help,
+            kind,
+            unit: None,
+            samples: Vec::new(),
+        }
+    }
+
+    fn unit(mut self, unit: &'static str) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    fn sample(mut self, labels: Vec<(&'static str, String)>, value: f64) -> Self {
+        self.samples.push(Sample { labels, value });
+        self
+    }
+
+    fn write(&self, out: &mut String, format: MetricsFormat) {
+        let family = format!("{}_{}", METRIC_PREFIX, self.name);
+        // Prometheus text names the family after its samples, while
+        // OpenMetrics drops the `_total` suffix from counter families.
+        let sample_name = match self.kind {
+            MetricKind::Counter => format!("{}_total", family),
+            MetricKind::Gauge => family.clone(),
+        };
+        let header_name = match format {
+            MetricsFormat::Prometheus => &sample_name,
+            MetricsFormat::OpenMetrics => &family,
+        };
+        let kind = match self.kind {
+            MetricKind::Gauge => "gauge",
+            MetricKind::Counter => "counter",
+        };
+
+        let _ = writeln!(out, "# HELP {} {}", header_name, self.help);
+        let _ = writeln!(out, "# TYPE {} {}", header_name, kind);
+        if let (MetricsFormat::OpenMetrics, Some(unit)) = (format, self.unit) {
+            let _ = writeln!(out, "# UNIT {} {}", header_name, unit);
+        }
+
+        for sample in &self.samples {
+            out.push_str(&sample_name);
+            if !sample.labels.is_empty() {
+                let labels: Vec<String> = sample
+                    .labels
+                    .iter()
+                    .map(|(name, value)| format!("{}=\"{}\"", name, escape_label_value(value)))
+                    .collect();
+                let _ = write!(out, "{{{}}}", labels.join(","));
+            }
+            let _ = writeln!(out, " {}", sample.value);
+        }
+    }
+}
+
+/// Escape a label value for the text exposition formats
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Build the metric families exported for a snapshot
+fn families(info: &SystemInfo) -> Vec<MetricFamily> {
+    let mut filesystem_size = MetricFamily::new(
+        "filesystem_size_bytes",
+        "Filesystem size in bytes.",
+        MetricKind::Gauge,
+    )
+    .unit("bytes");
+    let mut filesystem_avail = MetricFamily::new(
+        "filesystem_avail_bytes",
+        "Filesystem space available in bytes.",
+        MetricKind::Gauge,
+    )
+    .unit("bytes");
+    let mut filesystem_files = MetricFamily::new(
+        "filesystem_files",
+        "Filesystem total inodes.",
+        MetricKind::Gauge,
+    );
+    let mut filesystem_files_free = MetricFamily::new(
+        "filesystem_files_free",
+        "Filesystem free inodes.",
+        MetricKind::Gauge,
+    );
+    for disk in &info.disks {
+        let labels = vec![
+            ("device", disk.name.clone()),
+            ("mount_point", disk.mount_point.clone()),
+            ("fs_type", disk.file_system.clone()),
+        ];
+        filesystem_size = filesystem_size.sample(labels.clone(), disk.total_space as f64);
+        filesystem_avail = filesystem_avail.sample(labels.clone(), disk.available_space as f64);
+        if let Some(inodes) = disk.inodes {
+            filesystem_files = filesystem_files.sample(labels.clone(), inodes.total as f64);
+            filesystem_files_free = filesystem_files_free.sample(labels, inodes.free as f64);
+        }
+    }
+
+    let mut network_receive = MetricFamily::new(
+        "network_receive_bytes",
+        "Network bytes received per interface.",
+        MetricKind::Counter,
+    )
+    .unit("bytes");
+    let mut network_transmit = MetricFamily::new(
+        "network_transmit_bytes",
+        "Network bytes transmitted per interface.",
+        MetricKind::Counter,
+    )
+    .unit("bytes");
+    for network in &info.networks {
+        let labels = vec![("interface", network.interface_name.clone())];
+        network_receive = network_receive.sample(labels.clone(), network.received_bytes as f64);
+        network_transmit = network_transmit.sample(labels, network.transmitted_bytes as f64);
+    }
+
+    let mut pressure_stalled = MetricFamily::new(
+        "pressure_stalled_seconds",
+        "Time tasks were stalled waiting on a resource (PSI); kind is some or full.",
+        MetricKind::Counter,
+    )
+    .unit("seconds");
+    for (resource, pressure) in info.pressure.entries() {
+        let stalls = [("some", Some(pressure.some)), ("full", pressure.full)];
+        for (kind, stall) in stalls {
+            if let Some(stall) = stall {
+                pressure_stalled = pressure_stalled.sample(
+                    vec![
+                        ("resource", resource.to_string()),
+                        ("kind", kind.to_string()),
+                    ],
+                    stall.total_us as f64 / 1_000_000.0,
+                );
+            }
+        }
+    }
+
+    vec![
+        MetricFamily::new(
+            "info",
+            "System identification; the value is always 1.",
+            MetricKind::Gauge,
+        )
+        .sample(
+            vec![
+                ("hostname", info.hostname.clone()),
+                ("os_name", info.os_name.clone()),
+                ("os_version", info.os_version.clone()),
+                ("kernel_version", info.kernel_version.clone()),
+            ],
+            1.0,
+        ),
+        MetricFamily::new(
+            "memory_total_bytes",
+            "Total physical memory in bytes.",
+            MetricKind::Gauge,
+        )
+        .unit("bytes")
+        .sample(Vec::new(), info.total_memory as f64),
+        MetricFamily::new(
+            "memory_used_bytes",
+            "Used physical memory in bytes.",
+            MetricKind::Gauge,
+        )
+        .unit("bytes")
+        .sample(Vec::new(), info.used_memory as f64),
+        MetricFamily::new(
+            "swap_total_bytes",
+            "Total swap space in bytes.",
+            MetricKind::Gauge,
+        )
+        .unit("bytes")
+        .sample(Vec::new(), info.total_swap as f64),
+        MetricFamily::new(
+            "swap_used_bytes",
+            "Used swap space in bytes.",
+            MetricKind::Gauge,
+        )
+        .unit("bytes")
+        .sample(Vec::new(), info.used_swap as f64),
+        filesystem_size,
+        filesystem_avail,
+        filesystem_files,
+        filesystem_files_free,
+        network_receive,
+        network_transmit,
+        MetricFamily::new(
+            "processes",
+            "Number of running processes.",
+            MetricKind::Gauge,
+        )
+        .sample(Vec::new(), info.processes_count as f64),
+        MetricFamily::new(
+            "uptime_seconds",
+            "System uptime in seconds.",
+            MetricKind::Gauge,
+        )
+        .unit("seconds")
+        .sample(Vec::new(), info.uptime as f64),
+        pressure_stalled,
+    ]
+}
+
+/// Sections gathered for each scrape; external network probes are never run
+///
+/// Throughput is left to the scraper's `rate()`, so scrapes do not wait to
+/// sample network rates.
+pub fn collect_options() -> CollectOptions {
+    CollectOptions::empty()
+        .sections([
+            Section::System,
+            Section::Memory,
+            Section::Disks,
+            Section::Networks,
+            Section::Processes,
+            Section::Pressure,
+        ])
+        .network_sample_interval(Duration::ZERO)
+}
+
+/// Encode a snapshot in the given exposition format
+pub fn encode(info: &SystemInfo, format: MetricsFormat) -> String {
+    let mut out = String::new();
+    for family in families(info) {
+        family.write(&mut out, format);
+    }
+    if format == MetricsFormat::OpenMetrics {
+        out.push_str("# EOF\n");
+    }
+    out
+}
+
+/// HTTP server exposing `/metrics`
+pub struct MetricsServer {
+    server: tiny_http::Server,
+    options: CollectOptions,
+}
+
+impl MetricsServer {
+    /// Bind the server to the given address, exporting the filesystems `disk_filter` matches
+    pub fn bind(addr: &str, disk_filter: DiskFilter) -> Result<Self> {
+        let server = tiny_http::Server::http(addr)
+            .map_err(|e| anyhow::anyhow!("failed to bind {}: {}", addr, e))?;
+        Ok(Self {
+            server,
+            options: collect_options().disk_filter(disk_filter),
+        })
+    }
+
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.server.server_addr().to_ip()
+    }
+
+    /// Serve requests until the process exits
+    pub fn run(&self) -> Result<()> {
+        for request in self.server.incoming_requests() {
+            // A failed response only affects that scrape; keep serving.
+            if let Err(e) = self.handle(request) {
+                eprintln!("Failed to answer metrics request: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle(&self, request: tiny_http::Request) -> Result<()> {
+        let path = request.url().split('?').next().unwrap_or_default();
+        let response = match (request.method(), path) {
+            (tiny_http::Method::Get, "/metrics") => {
+                let accept = request
+                    .headers()
+                    .iter()
+                    .find(|header| header.field.equiv("Accept"))
+                    .map(|header| header.value.as_str());
+                let format = MetricsFormat::negotiate(accept);
+                let body = encode(&SystemInfo::collect_with(&self.options), format);
+                tiny_http::Response::from_string(body)
+                    .with_header(content_type(format.content_type()))
+            }
+            (tiny_http::Method::Get, "/") => tiny_http::Response::from_string(
+                "<html><head><title>System Index Exporter</title></head>\
+                 <body><h1>System Index Exporter</h1>\
+                 <p><a href=\"/metrics\">Metrics</a></p></body></html>",
+            )
+            .with_header(content_type("text/html; charset=utf-8")),
+            _ => tiny_http::Response::from_string("Not Found").with_status_code(404),
+        };
+        request.respond(response)?;
+        Ok(())
+    }
+}
+
+fn content_type(value: &str) -> tiny_http::Header {
+    tiny_http::Header::from_bytes("Content-Type", value).expect("static header is valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        DiskInfo, InodeUsage, LinkInfo, NetworkInfo, Pressure, PressureStall, ResourcePressure,
+    };
+
+    fn sample_info() -> SystemInfo {
+        SystemInfo {
+            os_name: "Linux".to_string(),
+            os_version: "12".to_string(),
+            kernel_version: "6.1.0".to_string(),
+            hostname: "host\"1".to_string(),
+            cpu_count: 4,
+            cpu_brand: "Test CPU".to_string(),
+            total_memory: 8192,
+            used_memory: 4096,
+            total_swap: 1024,
+            used_swap: 0,
+            disks: vec![DiskInfo {
+                name: "/dev/sda1".to_string(),
+                mount_point: "/".to_string(),
+                total_space: 1000,
+                available_space: 400,
+                file_system: "ext4".to_string(),
+                inodes: InodeUsage::from_counts(100, 25),
+                dev: Some("8:1".to_string()),
+                source: Some("/dev/sda1".to_string()),
+                mount_options: vec!["rw".to_string(), "relatime".to_string()],
+                forecast: None,
+            }],
+            networks: vec![NetworkInfo {
+                interface_name: "eth0".to_string(),
+                received_bytes: 10,
+                transmitted_bytes: 20,
+                ip_address: None,
+                addresses: Vec::new(),
+                link: LinkInfo::default(),
+                rates: None,
+                stats: None,
+            }],
+            processes_count: 42,
+            uptime: 3600,
+            pressure: Pressure {
+                io: Some(ResourcePressure {
+                    some: PressureStall {
+                        total_us: 2_500_000,
+                        ..PressureStall::default()
+                    },
+                    full: None,
+                }),
+                ..Pressure::default()
+            },
+            ..SystemInfo::default()
+        }
+    }
+
+    #[test]
+    fn test_negotiate_format() {
+        assert_eq!(MetricsFormat::negotiate(None), MetricsFormat::Prometheus);
+        assert_eq!(
+            MetricsFormat::negotiate(Some("text/plain;version=0.0.4")),
+            MetricsFormat::Prometheus
+        );
+        assert_eq!(
+            MetricsFormat::negotiate(Some(
+                "application/openmetrics-text;version=1.0.0,text/plain;q=0.5"
+            )),
+            MetricsFormat::OpenMetrics
+        );
+    }
+
+    #[test]
+    fn test_encode_prometheus() {
+        let output = encode(&sample_info(), MetricsFormat::Prometheus);
+
+        assert!(output.contains("# TYPE system_index_memory_total_bytes gauge"));
+        assert!(output.contains("system_index_memory_total_bytes 8192\n"));
+        assert!(output.contains("# TYPE system_index_network_receive_bytes_total counter"));
+        assert!(
+            output.contains("system_index_network_receive_bytes_total{interface=\"eth0\"} 10\n")
+        );
+        assert!(output.contains(
+            "system_index_filesystem_avail_bytes{device=\"/dev/sda1\",mount_point=\"/\",fs_type=\"ext4\"} 400\n"
+        ));
+        assert!(output.contains(
+            "system_index_filesystem_files_free{device=\"/dev/sda1\",mount_point=\"/\",fs_type=\"ext4\"} 25\n"
+        ));
+        assert!(output.contains(
+            "system_index_filesystem_files{device=\"/dev/sda1\",mount_point=\"/\",fs_type=\"ext4\"} 100\n"
+        ));
+        assert!(output.contains("hostname=\"host\\\"1\""));
+        assert!(output.contains(
+            "system_index_pressure_stalled_seconds_total{resource=\"io\",kind=\"some\"} 2.5\n"
+        ));
+        assert!(!output.contains("# EOF"));
+        assert!(!output.contains("# UNIT"));
+    }
+
+    #[test]
+    fn test_encode_openmetrics() {
+        let output = encode(&sample_info(), MetricsFormat::OpenMetrics);
+
+        assert!(output.contains("# TYPE system_index_network_transmit_bytes counter"));
+        assert!(output.contains("# UNIT system_index_network_transmit_bytes bytes"));
+        assert!(
+            output.contains("system_index_network_transmit_bytes_total{interface=\"eth0\"} 20\n")
+        );
+        assert!(output.contains("# UNIT system_index_uptime_seconds seconds"));
+        assert!(output.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn test_server_uses_disk_filter() {
+        let filter = DiskFilter {
+            fs_types: vec!["xfs".to_string()],
+            ..DiskFilter::default()
+        };
+        let server = MetricsServer::bind("127.0.0.1:0", filter.clone()).unwrap();
+        assert_eq!(server.options.disk_filter, filter);
+        assert!(server.options.includes(Section::Disks));
+    }
+
+    #[test]
+    fn test_escape_label_value() {
+        assert_eq!(escape_label_value("a\\b\"c\nd"), "a\\\\b\\\"c\\nd");
+    }
+}
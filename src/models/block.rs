@@ -0,0 +1,372 @@
+use super::mountinfo::MountEntry;
+use super::swap::SwapDevice;
+use super::{SystemInfo, SECTOR_SIZE, SYSFS_BLOCK_DIR};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Every block device, including partitions, relative to the sysfs root
+const SYSFS_CLASS_BLOCK_DIR: &str = "class/block";
+
+/// Stacked devices deeper than this are not followed
+const MAX_DEPTH: usize = 8;
+
+/// A block device and the devices built on top of it, like a row of `lsblk`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockDevice {
+    /// Kernel name, e.g. `nvme0n1p2` or `dm-0`
+    pub name: String,
+    pub kind: BlockDeviceKind,
+    /// `major:minor`
+    pub dev: String,
+    pub size_bytes: u64,
+    pub model: Option<String>,
+    pub serial: Option<String>,
+    pub rotational: bool,
+    pub removable: bool,
+    /// Device-mapper name, e.g. `vg0-root`
+    pub dm_name: Option<String>,
+    /// Level of md arrays, e.g. `raid1`
+    pub raid_level: Option<String>,
+    /// Where the device is mounted; `[SWAP]` for active swap
+    pub mount_points: Vec<String>,
+    /// Partitions and holders (device-mapper targets and md arrays)
+    pub children: Vec<BlockDevice>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockDeviceKind {
+    Disk,
+    Partition,
+    /// LVM logical volume
+    Lvm,
+    /// dm-crypt mapping such as LUKS
+    Crypt,
+    /// Other device-mapper target
+    Dm,
+    /// md software RAID array
+    Raid,
+    Loop,
+    Rom,
+}
+
+impl BlockDevice {
+    /// Read the block device tree below `sysfs_root`, with mount points from
+    /// `proc_root/self/mountinfo` and swap areas from `proc_root/swaps`
+    ///
+    /// Roots are whole devices that are not built on other devices; stacked
+    /// devices spanning several parents appear under each of them.
+    /// Zero-sized devices such as unattached loop devices are skipped.
+    pub fn read_tree(sysfs_root: &Path, proc_root: &Path) -> Vec<Self> {
+        let Ok(entries) = std::fs::read_dir(sysfs_root.join(SYSFS_BLOCK_DIR)) else {
+            return Vec::new();
+        };
+        let mounts = read_mounts(proc_root);
+
+        let mut roots: Vec<Self> = entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let name = entry.file_name().to_str()?.to_string();
+                if has_entries(&entry.path().join("slaves")) {
+                    return None;
+                }
+                Self::read(sysfs_root, &entry.path(), name, &mounts, 0)
+            })
+            .collect();
+        roots.sort_by(|a, b| a.name.cmp(&b.name));
+        roots
+    }
+
+    fn read(
+        sysfs_root: &Path,
+        dir: &Path,
+        name: String,
+        mounts: &HashMap<String, Vec<String>>,
+        depth: usize,
+    ) -> Option<Self> {
+        let read = |file: &str| {
+            std::fs::read_to_string(dir.join(file))
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let size_bytes = read("size")?.parse::<u64>().ok()? * SECTOR_SIZE;
+        if size_bytes == 0 {
+            return None;
+        }
+        let dev = read("dev").unwrap_or_default();
+        let dm_uuid = read("dm/uuid").unwrap_or_default();
+        let raid_level = read("md/level");
+        let kind = if dir.join("partition").exists() {
+            BlockDeviceKind::Partition
+        } else if dm_uuid.starts_with("LVM-") {
+            BlockDeviceKind::Lvm
+        } else if dm_uuid.starts_with("CRYPT-") {
+            BlockDeviceKind::Crypt
+        } else if dir.join("dm").exists() {
+            BlockDeviceKind::Dm
+        } else if raid_level.is_some() {
+            BlockDeviceKind::Raid
+        } else if name.starts_with("loop") {
+            BlockDeviceKind::Loop
+        } else if name.starts_with("sr") {
+            BlockDeviceKind::Rom
+        } else {
+            BlockDeviceKind::Disk
+        };
+
+        let mut children = Vec::new();
+        if depth < MAX_DEPTH {
+            children.extend(partitions(dir).into_iter().filter_map(|(part, part_dir)| {
+                Self::read(sysfs_root, &part_dir, part, mounts, depth + 1)
+            }));
+            children.extend(
+                entry_names(&dir.join("holders"))
+                    .into_iter()
+                    .filter_map(|holder| {
+                        let holder_dir = resolve(sysfs_root, &holder)?;
+                        Self::read(sysfs_root, &holder_dir, holder, mounts, depth + 1)
+                    }),
+            );
+        }
+
+        let mut mount_points = mounts.get(&dev).cloned().unwrap_or_default();
+        if let Some(swap) = mounts.get(&format!("/dev/{}", name)) {
+            mount_points.extend(swap.iter().cloned());
+        }
+
+        Some(Self {
+            kind,
+            model: read("device/model"),
+            serial: read("device/serial").or_else(|| read("serial")),
+            rotational: read("queue/rotational").as_deref() == Some("1"),
+            removable: read("removable").as_deref() == Some("1"),
+            dm_name: read("dm/name"),
+            raid_level,
+            mount_points,
+            children,
+            name,
+            dev,
+            size_bytes,
+        })
+    }
+
+    /// Name shown to users: the device-mapper name when there is one
+    pub fn display_name(&self) -> &str {
+        self.dm_name.as_deref().unwrap_or(&self.name)
+    }
+
+    /// `lsblk`-style type column, e.g. `part` or `raid1`
+    pub fn kind_label(&self) -> &str {
+        match self.kind {
+            BlockDeviceKind::Disk => "disk",
+            BlockDeviceKind::Partition => "part",
+            BlockDeviceKind::Lvm => "lvm",
+            BlockDeviceKind::Crypt => "crypt",
+            BlockDeviceKind::Dm => "dm",
+            BlockDeviceKind::Raid => self.raid_level.as_deref().unwrap_or("raid"),
+            BlockDeviceKind::Loop => "loop",
+            BlockDeviceKind::Rom => "rom",
+        }
+    }
+
+    /// Render devices as an indented tree with aligned columns
+    pub fn tree_lines(devices: &[Self]) -> Vec<String> {
+        let mut rows = Vec::new();
+        for device in devices {
+            device.collect_rows(String::new(), String::new(), &mut rows);
+        }
+        let width = rows
+            .iter()
+            .map(|(name, _)| name.chars().count())
+            .max()
+            .unwrap_or(0);
+        rows.into_iter()
+            .map(|(name, details)| {
+                let padding = width - name.chars().count();
+                format!("{}{}  {}", name, " ".repeat(padding), details)
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    /// Push `(tree prefix + name, details)` for this device and its descendants
+    fn collect_rows(&self, prefix: String, child_prefix: String, rows: &mut Vec<(String, String)>) {
+        let mut notes = Vec::new();
+        if self.kind == BlockDeviceKind::Disk {
+            notes.extend(self.model.clone());
+            notes.extend(self.serial.as_ref().map(|serial| format!("SN {}", serial)));
+            notes.push(if self.rotational { "HDD" } else { "SSD" }.to_string());
+        }
+        if self.removable {
+            notes.push("removable".to_string());
+        }
+        notes.extend(self.mount_points.iter().cloned());
+
+        rows.push((
+            format!("{}{}", prefix, self.display_name()),
+            format!(
+                "{:<6} {:>10}  {}",
+                self.kind_label(),
+                SystemInfo::format_bytes(self.size_bytes),
+                notes.join(", ")
+            ),
+        ));
+
+        for (index, child) in self.children.iter().enumerate() {
+            let last = index + 1 == self.children.len();
+            child.collect_rows(
+                format!("{}{}", child_prefix, if last { "└─" } else { "├─" }),
+                format!("{}{}", child_prefix, if last { "  " } else { "│ " }),
+                rows,
+            );
+        }
+    }
+}
+
+/// Mount points keyed by `major:minor`, plus `[SWAP]` keyed by swap device path
+fn read_mounts(proc_root: &Path) -> HashMap<String, Vec<String>> {
+    let mut mounts: HashMap<String, Vec<String>> = HashMap::new();
+    for mount in MountEntry::read(&proc_root.join("self/mountinfo")) {
+        mounts.entry(mount.dev).or_default().push(mount.mount_point);
+    }
+    for swap in SwapDevice::read(&proc_root.join("swaps")) {
+        if swap.kind == "partition" {
+            mounts
+                .entry(swap.path)
+                .or_default()
+                .push("[SWAP]".to_string());
+        }
+    }
+    mounts
+}
+
+/// Partition subdirectories of a whole device, in partition number order
+fn partitions(dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut partitions: Vec<(u32, String, PathBuf)> = entry_names(dir)
+        .into_iter()
+        .filter_map(|name| {
+            let path = dir.join(&name);
+            let number = std::fs::read_to_string(path.join("partition")).ok()?;
+            Some((number.trim().parse().ok()?, name, path))
+        })
+        .collect();
+    partitions.sort();
+    partitions
+        .into_iter()
+        .map(|(_, name, path)| (name, path))
+        .collect()
+}
+
+/// Directory of a device by kernel name, whole device or partition
+fn resolve(sysfs_root: &Path, name: &str) -> Option<PathBuf> {
+    [SYSFS_BLOCK_DIR, SYSFS_CLASS_BLOCK_DIR]
+        .iter()
+        .map(|dir| sysfs_root.join(dir).join(name))
+        .find(|path| path.exists())
+}
+
+/// Sorted entry names of a directory; empty when it is missing
+fn entry_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok()?.file_name().to_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+fn has_entries(dir: &Path) -> bool {
+    !entry_names(dir).is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixture_path;
+
+    #[test]
+    fn test_read_fixture_roots() {
+        let tree = BlockDevice::read_tree(&fixture_path("sysfs"), &fixture_path("proc"));
+        let roots: Vec<&str> = tree.iter().map(|d| d.name.as_str()).collect();
+        // dm-* and md0 sit on other devices; zram1 is zero-sized
+        assert_eq!(roots, ["nvme0n1", "sda", "sdb", "sdc", "zram0"]);
+
+        let nvme = &tree[0];
+        assert_eq!(nvme.kind, BlockDeviceKind::Disk);
+        assert_eq!(nvme.model.as_deref(), Some("Samsung SSD 980 PRO 1TB"));
+        assert_eq!(nvme.serial.as_deref(), Some("S5GXNF0R123456"));
+        assert!(!nvme.rotational);
+        assert_eq!(nvme.size_bytes, 1_953_525_168 * 512);
+
+        let usb = &tree[3];
+        assert!(usb.removable);
+        assert_eq!(usb.children[0].mount_points, ["/media/usb stick"]);
+        assert_eq!(tree[4].mount_points, ["[SWAP]"]);
+    }
+
+    #[test]
+    fn test_stacked_devices() {
+        let tree = BlockDevice::read_tree(&fixture_path("sysfs"), &fixture_path("proc"));
+        let nvme = &tree[0];
+        let parts: Vec<&str> = nvme.children.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(parts, ["nvme0n1p1", "nvme0n1p2", "nvme0n1p3", "nvme0n1p4"]);
+        assert_eq!(nvme.children[0].mount_points, ["/boot/efi"]);
+        assert_eq!(nvme.children[2].mount_points, ["[SWAP]"]);
+
+        let luks = &nvme.children[3].children[0];
+        assert_eq!(luks.kind, BlockDeviceKind::Crypt);
+        assert_eq!(luks.display_name(), "cryptroot");
+        let volumes: Vec<(&str, BlockDeviceKind, &[String])> = luks
+            .children
+            .iter()
+            .map(|d| (d.display_name(), d.kind, d.mount_points.as_slice()))
+            .collect();
+        assert_eq!(
+            volumes,
+            [
+                ("vg0-root", BlockDeviceKind::Lvm, &["/".to_string()][..]),
+                ("vg0-home", BlockDeviceKind::Lvm, &["/home".to_string()][..]),
+            ]
+        );
+
+        // The mirror shows up under both members
+        for disk in &tree[1..3] {
+            let md = &disk.children[0].children[0];
+            assert_eq!(md.kind, BlockDeviceKind::Raid);
+            assert_eq!(md.kind_label(), "raid1");
+            assert_eq!(md.mount_points, ["/srv"]);
+        }
+    }
+
+    #[test]
+    fn test_tree_lines() {
+        let tree = BlockDevice::read_tree(&fixture_path("sysfs"), &fixture_path("proc"));
+        let lines = BlockDevice::tree_lines(&tree[..1]);
+        assert_eq!(
+            lines,
+            [
+                "nvme0n1         disk    931.51 GB  Samsung SSD 980 PRO 1TB, SN S5GXNF0R123456, SSD",
+                "├─nvme0n1p1     part    512.00 MB  /boot/efi",
+                "├─nvme0n1p2     part      1.00 GB  /boot",
+                "├─nvme0n1p3     part      8.00 GB  [SWAP]",
+                "└─nvme0n1p4     part    922.01 GB",
+                "  └─cryptroot   crypt   922.00 GB",
+                "    ├─vg0-root  lvm     100.00 GB  /",
+                "    └─vg0-home  lvm     800.00 GB  /home",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_sysfs() {
+        assert!(
+            BlockDevice::read_tree(Path::new("/nonexistent"), Path::new("/nonexistent")).is_empty()
+        );
+    }
+}
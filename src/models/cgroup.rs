@@ -0,0 +1,343 @@
+use super::topology::parse_cpu_list;
+use super::SystemInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// cgroup filesystem mount point relative to the sysfs root
+pub const SYSFS_CGROUP_DIR: &str = "fs/cgroup";
+
+/// cgroup v1 reports "no limit" as a page-aligned `i64::MAX`; anything this large is unlimited
+const V1_UNLIMITED_THRESHOLD: u64 = 1 << 62;
+
+/// cgroup hierarchy flavour
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CgroupVersion {
+    V1,
+    V2,
+}
+
+impl std::fmt::Display for CgroupVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CgroupVersion::V1 => write!(f, "v1"),
+            CgroupVersion::V2 => write!(f, "v2"),
+        }
+    }
+}
+
+/// Resource limits of the cgroup this process runs in
+///
+/// `None` limits mean unlimited. Limits set on ancestor cgroups are taken into
+/// account, so the values are what actually constrains this process.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CgroupLimits {
+    pub version: CgroupVersion,
+    /// cgroup path, e.g. `/system.slice/app.service` (the memory controller's path on v1)
+    pub path: String,
+    pub memory_limit_bytes: Option<u64>,
+    pub memory_usage_bytes: Option<u64>,
+    /// CPU bandwidth quota, in CPUs (`quota / period`)
+    pub cpu_quota: Option<f64>,
+    /// CPUs the cgroup may run on; empty when unknown
+    pub cpuset: Vec<usize>,
+    pub pids_limit: Option<u64>,
+    pub pids_current: Option<u64>,
+}
+
+impl CgroupLimits {
+    /// Read the limits of the current process from `proc_root/self/cgroup` and
+    /// the cgroup filesystem below `sysfs_root`
+    ///
+    /// Returns `None` when the process is not in a cgroup hierarchy we can read.
+    pub fn read(proc_root: &Path, sysfs_root: &Path) -> Option<Self> {
+        let membership = std::fs::read_to_string(proc_root.join("self/cgroup")).ok()?;
+        let cgroup_root = sysfs_root.join(SYSFS_CGROUP_DIR);
+
+        if cgroup_root.join("cgroup.controllers").exists() {
+            Self::read_v2(&membership, &cgroup_root)
+        } else {
+            Self::read_v1(&membership, &cgroup_root)
+        }
+    }
+
+    fn read_v2(membership: &str, root: &Path) -> Option<Self> {
+        // The unified hierarchy is listed as `0::/path`
+        let path = parse_membership(membership).remove("")?;
+        let dir = resolve(root, &path);
+
+        Some(Self {
+            version: CgroupVersion::V2,
+            path,
+            memory_limit_bytes: tightest(&dir, root, |dir| read_max(&dir.join("memory.max"))),
+            memory_usage_bytes: read_number(&dir.join("memory.current")),
+            cpu_quota: tightest(&dir, root, |dir| {
+                let contents = std::fs::read_to_string(dir.join("cpu.max")).ok()?;
+                let (quota, period) = contents.trim().split_once(' ')?;
+                cpu_quota(quota.parse().ok()?, period.parse().ok()?)
+            }),
+            cpuset: read_cpuset(&dir, &["cpuset.cpus.effective", "cpuset.cpus"]),
+            pids_limit: tightest(&dir, root, |dir| read_max(&dir.join("pids.max"))),
+            pids_current: read_number(&dir.join("pids.current")),
+        })
+    }
+
+    fn read_v1(membership: &str, root: &Path) -> Option<Self> {
+        let paths = parse_membership(membership);
+        let controller = |name: &str| {
+            let base = root.join(name);
+            let path = paths.get(name)?;
+            base.exists().then(|| (resolve(&base, path), base))
+        };
+
+        let memory = controller("memory");
+        let cpu = controller("cpu");
+        let cpuset = controller("cpuset");
+        let pids = controller("pids");
+        if memory.is_none() && cpu.is_none() && cpuset.is_none() && pids.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            version: CgroupVersion::V1,
+            path: paths
+                .get("memory")
+                .cloned()
+                .unwrap_or_else(|| "/".to_string()),
+            memory_limit_bytes: memory.as_ref().and_then(|(dir, base)| {
+                tightest(dir, base, |dir| {
+                    read_number(&dir.join("memory.limit_in_bytes"))
+                        .filter(|limit| *limit < V1_UNLIMITED_THRESHOLD)
+                })
+            }),
+            memory_usage_bytes: memory
+                .as_ref()
+                .and_then(|(dir, _)| read_number(&dir.join("memory.usage_in_bytes"))),
+            cpu_quota: cpu.as_ref().and_then(|(dir, base)| {
+                tightest(dir, base, |dir| {
+                    // A quota of -1 means unlimited and fails to parse as u64
+                    cpu_quota(
+                        read_number(&dir.join("cpu.cfs_quota_us"))?,
+                        read_number(&dir.join("cpu.cfs_period_us"))?,
+                    )
+                })
+            }),
+            cpuset: cpuset
+                .map(|(dir, _)| read_cpuset(&dir, &["cpuset.effective_cpus", "cpuset.cpus"]))
+                .unwrap_or_default(),
+            pids_limit: pids
+                .as_ref()
+                .and_then(|(dir, base)| tightest(dir, base, |dir| read_max(&dir.join("pids.max")))),
+            pids_current: pids
+                .as_ref()
+                .and_then(|(dir, _)| read_number(&dir.join("pids.current"))),
+        })
+    }
+
+    /// CPUs actually usable: the host count capped by the cpuset and CPU quota
+    pub fn effective_cpus(&self, host_cpus: usize) -> f64 {
+        let mut cpus = host_cpus as f64;
+        if !self.cpuset.is_empty() {
+            cpus = cpus.min(self.cpuset.len() as f64);
+        }
+        if let Some(quota) = self.cpu_quota {
+            cpus = cpus.min(quota);
+        }
+        cpus
+    }
+
+    /// Memory actually usable: host RAM capped by the memory limit
+    pub fn effective_memory(&self, host_memory: u64) -> u64 {
+        self.memory_limit_bytes
+            .map_or(host_memory, |limit| limit.min(host_memory))
+    }
+
+    /// Whether the cgroup allows less CPU or memory than the host has
+    pub fn is_constrained(&self, host_cpus: usize, host_memory: u64) -> bool {
+        self.effective_cpus(host_cpus) < host_cpus as f64
+            || self.effective_memory(host_memory) < host_memory
+    }
+
+    /// Effective limits next to host values, one line per resource
+    pub fn summary_lines(&self, host_cpus: usize, host_memory: u64) -> Vec<String> {
+        let limit = |value: Option<String>| value.unwrap_or_else(|| "unlimited".to_string());
+        let cpuset = if self.cpuset.is_empty() {
+            String::new()
+        } else {
+            format!(", cpuset of {}", self.cpuset.len())
+        };
+
+        vec![
+            format!(
+                "CPU:    {:.2} of {} host CPUs (quota {}{})",
+                self.effective_cpus(host_cpus),
+                host_cpus,
+                limit(self.cpu_quota.map(|quota| format!("{:.2}", quota))),
+                cpuset
+            ),
+            format!(
+                "Memory: {} of {} host (limit {}, used {})",
+                SystemInfo::format_bytes(self.effective_memory(host_memory)),
+                SystemInfo::format_bytes(host_memory),
+                limit(self.memory_limit_bytes.map(SystemInfo::format_bytes)),
+                self.memory_usage_bytes
+                    .map_or_else(|| "unknown".to_string(), SystemInfo::format_bytes)
+            ),
+            format!(
+                "PIDs:   {} of {}",
+                self.pids_current
+                    .map_or_else(|| "unknown".to_string(), |pids| pids.to_string()),
+                limit(self.pids_limit.map(|pids| pids.to_string()))
+            ),
+        ]
+    }
+}
+
+/// Map each controller in `/proc/self/cgroup` to its path; the v2 hierarchy maps from `""`
+fn parse_membership(contents: &str) -> HashMap<String, String> {
+    let mut paths = HashMap::new();
+    for line in contents.lines() {
+        let mut fields = line.splitn(3, ':');
+        let (Some(_), Some(controllers), Some(path)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        for controller in controllers.split(',') {
+            paths.insert(controller.to_string(), path.to_string());
+        }
+    }
+    paths
+}
+
+/// Directory of `path` below `base`
+///
+/// Inside a cgroup namespace the listed path may not exist in the mounted
+/// tree, whose root is then our own cgroup.
+fn resolve(base: &Path, path: &str) -> PathBuf {
+    let dir = base.join(path.trim_start_matches('/'));
+    if dir.is_dir() {
+        dir
+    } else {
+        base.to_path_buf()
+    }
+}
+
+/// Smallest value returned by `read` for `dir` and each ancestor up to `root`
+fn tightest<T: PartialOrd>(
+    dir: &Path,
+    root: &Path,
+    read: impl Fn(&Path) -> Option<T>,
+) -> Option<T> {
+    let mut tightest: Option<T> = None;
+    for ancestor in dir.ancestors() {
+        if let Some(value) = read(ancestor) {
+            if tightest.as_ref().is_none_or(|current| value < *current) {
+                tightest = Some(value);
+            }
+        }
+        if ancestor == root {
+            break;
+        }
+    }
+    tightest
+}
+
+fn cpu_quota(quota: u64, period: u64) -> Option<f64> {
+    (period > 0).then(|| quota as f64 / period as f64)
+}
+
+fn read_number(path: &Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Read a v2-style limit where `max` means unlimited
+fn read_max(path: &Path) -> Option<u64> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    match contents.trim() {
+        "max" => None,
+        value => value.parse().ok(),
+    }
+}
+
+fn read_cpuset(dir: &Path, files: &[&str]) -> Vec<usize> {
+    files
+        .iter()
+        .filter_map(|file| std::fs::read_to_string(dir.join(file)).ok())
+        .map(|contents| parse_cpu_list(&contents))
+        .find(|cpus| !cpus.is_empty())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixture_path;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn test_parse_membership() {
+        let paths =
+            parse_membership("3:cpu,cpuacct:/docker/abc\n1:name=systemd:/init.scope\n0::/\n");
+        assert_eq!(paths["cpu"], "/docker/abc");
+        assert_eq!(paths["cpuacct"], "/docker/abc");
+        assert_eq!(paths[""], "/");
+    }
+
+    #[test]
+    fn test_read_fixture_v2() {
+        let limits = CgroupLimits::read(&fixture_path("proc"), &fixture_path("sysfs")).unwrap();
+        assert_eq!(limits.version, CgroupVersion::V2);
+        assert_eq!(limits.path, "/system.slice/app.service");
+        // The parent slice's 4 GiB limit is tighter than the service's own 8 GiB
+        assert_eq!(limits.memory_limit_bytes, Some(4 * GIB));
+        assert_eq!(limits.memory_usage_bytes, Some(GIB));
+        assert_eq!(limits.cpu_quota, Some(1.5));
+        assert_eq!(limits.cpuset, vec![0, 1, 2, 3]);
+        assert_eq!(limits.pids_limit, Some(512));
+        assert_eq!(limits.pids_current, Some(37));
+
+        assert_eq!(limits.effective_cpus(8), 1.5);
+        assert_eq!(limits.effective_memory(16 * GIB), 4 * GIB);
+        assert_eq!(limits.effective_memory(2 * GIB), 2 * GIB);
+        assert!(limits.is_constrained(8, 16 * GIB));
+        assert_eq!(
+            limits.summary_lines(8, 16 * GIB)[0],
+            "CPU:    1.50 of 8 host CPUs (quota 1.50, cpuset of 4)"
+        );
+    }
+
+    #[test]
+    fn test_read_fixture_v1() {
+        let root = fixture_path("cgroup_v1");
+        let limits = CgroupLimits::read(&root.join("proc"), &root.join("sysfs")).unwrap();
+        assert_eq!(limits.version, CgroupVersion::V1);
+        assert_eq!(limits.path, "/docker/abc");
+        assert_eq!(limits.memory_limit_bytes, Some(2 * GIB));
+        assert_eq!(limits.memory_usage_bytes, Some(GIB / 2));
+        assert_eq!(limits.cpu_quota, Some(2.0));
+        assert_eq!(limits.cpuset, vec![0, 1]);
+        assert_eq!(limits.pids_limit, Some(1024));
+        assert_eq!(limits.effective_cpus(8), 2.0);
+        assert_eq!(limits.summary_lines(8, 16 * GIB)[2], "PIDs:   12 of 1024");
+    }
+
+    #[test]
+    fn test_unlimited_v1_root() {
+        let root = fixture_path("cgroup_v1");
+        let membership = "4:memory:/\n3:cpu,cpuacct:/\n";
+        let limits =
+            CgroupLimits::read_v1(membership, &root.join("sysfs").join(SYSFS_CGROUP_DIR)).unwrap();
+        assert_eq!(limits.memory_limit_bytes, None);
+        assert_eq!(limits.cpu_quota, None);
+        assert!(!limits.is_constrained(8, 16 * GIB));
+        assert!(limits.summary_lines(8, 16 * GIB)[1].contains("limit unlimited"));
+    }
+
+    #[test]
+    fn test_missing_cgroup() {
+        assert!(CgroupLimits::read(Path::new("/nonexistent"), Path::new("/nonexistent")).is_none());
+    }
+}
@@ -0,0 +1,254 @@
+use super::filesystem::DiskFilter;
+use crate::probes::{self, PublicIpResolver};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Default timeout for each public IP lookup
+const DEFAULT_PUBLIC_IP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default timeout for the bandwidth benchmark download
+const DEFAULT_BANDWIDTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default window over which network throughput is measured
+const DEFAULT_NETWORK_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Window for disk I/O rates where they are shown; rates are off by default
+/// so collections that do not display them never wait
+pub const DISK_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A group of related fields in [`SystemInfo`](super::SystemInfo)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Section {
+    /// OS name and version, kernel version, hostname and uptime
+    System,
+    /// CPU brand, core count, per-core usage and frequency, load average and
+    /// time breakdown
+    Cpu,
+    /// RAM and swap usage, `/proc/meminfo` breakdown, swap devices, zram,
+    /// zswap and huge pages
+    Memory,
+    /// Mounted filesystems, block device tree, I/O rates and md RAID arrays
+    Disks,
+    /// Network interfaces and their counters
+    Networks,
+    /// Local IP, plus public IP and bandwidth when their probes are enabled
+    NetworkDetails,
+    /// Running process count
+    Processes,
+    /// Pressure Stall Information for CPU, memory and I/O
+    Pressure,
+    /// cgroup v1/v2 memory, CPU, cpuset and PID limits of this process
+    Cgroup,
+}
+
+impl Section {
+    /// Every section, in display order
+    pub const ALL: [Section; 9] = [
+        Section::System,
+        Section::Cpu,
+        Section::Memory,
+        Section::Disks,
+        Section::Networks,
+        Section::NetworkDetails,
+        Section::Processes,
+        Section::Pressure,
+        Section::Cgroup,
+    ];
+}
+
+/// Options controlling what [`SystemInfo::collect_with`](super::SystemInfo::collect_with) gathers
+///
+/// Fields belonging to sections that are not requested keep their default
+/// values (zero, empty or `None`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectOptions {
+    pub sections: Vec<Section>,
+    pub public_ip: bool,
+    pub bandwidth: bool,
+    pub public_ip_timeout: Duration,
+    pub bandwidth_timeout: Duration,
+    pub public_ip_resolvers: Vec<PublicIpResolver>,
+    pub bandwidth_urls: Vec<String>,
+    /// Time between the two samples used for CPU usage and time breakdown
+    pub cpu_sample_interval: Duration,
+    /// Time between the two samples used for disk I/O rates; zero, the default, skips rates
+    pub disk_sample_interval: Duration,
+    /// Time between the two samples used for network throughput; zero skips rates
+    pub network_sample_interval: Duration,
+    /// Mounted filesystems to report; pseudo filesystems are hidden by default
+    pub disk_filter: DiskFilter,
+}
+
+impl CollectOptions {
+    /// Options collecting every section
+    ///
+    /// External network probes are opt-in and start disabled, so no outbound
+    /// connection is made unless [`CollectOptions::network_probes`] enables them.
+    pub fn new() -> Self {
+        Self {
+            sections: Section::ALL.to_vec(),
+            public_ip: false,
+            bandwidth: false,
+            public_ip_timeout: DEFAULT_PUBLIC_IP_TIMEOUT,
+            bandwidth_timeout: DEFAULT_BANDWIDTH_TIMEOUT,
+            public_ip_resolvers: probes::default_public_ip_resolvers(),
+            bandwidth_urls: probes::default_bandwidth_urls(),
+            cpu_sample_interval: sysinfo::MINIMUM_CPU_UPDATE_INTERVAL,
+            disk_sample_interval: Duration::ZERO,
+            network_sample_interval: DEFAULT_NETWORK_SAMPLE_INTERVAL,
+            disk_filter: DiskFilter::default(),
+        }
+    }
+
+    /// Options collecting nothing; add sections with [`CollectOptions::section`]
+    pub fn empty() -> Self {
+        Self {
+            sections: Vec::new(),
+            ..Self::new()
+        }
+    }
+
+    /// Add a section to collect
+    pub fn section(mut self, section: Section) -> Self {
+        if !self.sections.contains(&section) {
+            self.sections.push(section);
+        }
+        self
+    }
+
+    /// Add several sections to collect
+    pub fn sections(self, sections: impl IntoIterator<Item = Section>) -> Self {
+        sections.into_iter().fold(self, Self::section)
+    }
+
+    /// Stop collecting a section
+    pub fn without_section(mut self, section: Section) -> Self {
+        self.sections.retain(|s| *s != section);
+        self
+    }
+
+    /// Enable or disable all external network probes
+    pub fn network_probes(self, enabled: bool) -> Self {
+        self.public_ip(enabled).bandwidth(enabled)
+    }
+
+    /// Enable or disable the public IP lookup
+    pub fn public_ip(mut self, enabled: bool) -> Self {
+        self.public_ip = enabled;
+        self
+    }
+
+    /// Enable or disable the bandwidth benchmark
+    pub fn bandwidth(mut self, enabled: bool) -> Self {
+        self.bandwidth = enabled;
+        self
+    }
+
+    /// Timeout for each public IP lookup
+    pub fn public_ip_timeout(mut self, timeout: Duration) -> Self {
+        self.public_ip_timeout = timeout;
+        self
+    }
+
+    /// Timeout for the bandwidth benchmark download
+    pub fn bandwidth_timeout(mut self, timeout: Duration) -> Self {
+        self.bandwidth_timeout = timeout;
+        self
+    }
+
+    /// Resolvers tried in order for the public IP lookup
+    pub fn public_ip_resolvers(mut self, resolvers: Vec<PublicIpResolver>) -> Self {
+        self.public_ip_resolvers = resolvers;
+        self
+    }
+
+    /// Downloads tried in order for the bandwidth benchmark
+    pub fn bandwidth_urls(mut self, urls: Vec<String>) -> Self {
+        self.bandwidth_urls = urls;
+        self
+    }
+
+    /// Time between the two CPU samples; raised to sysinfo's minimum if shorter
+    pub fn cpu_sample_interval(mut self, interval: Duration) -> Self {
+        self.cpu_sample_interval = interval.max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+        self
+    }
+
+    /// Window over which disk I/O rates are measured; zero skips rates
+    pub fn disk_sample_interval(mut self, interval: Duration) -> Self {
+        self.disk_sample_interval = interval;
+        self
+    }
+
+    /// Window over which network throughput is measured; zero skips rates
+    pub fn network_sample_interval(mut self, interval: Duration) -> Self {
+        self.network_sample_interval = interval;
+        self
+    }
+
+    /// Mounted filesystems to report
+    pub fn disk_filter(mut self, filter: DiskFilter) -> Self {
+        self.disk_filter = filter;
+        self
+    }
+
+    /// Whether a section will be collected
+    pub fn includes(&self, section: Section) -> bool {
+        self.sections.contains(&section)
+    }
+
+    /// Whether the public IP lookup will run
+    pub fn probes_public_ip(&self) -> bool {
+        self.public_ip && self.includes(Section::NetworkDetails)
+    }
+
+    /// Whether the bandwidth benchmark will run
+    pub fn probes_bandwidth(&self) -> bool {
+        self.bandwidth && self.includes(Section::NetworkDetails)
+    }
+}
+
+impl Default for CollectOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_collects_everything_offline() {
+        let options = CollectOptions::default();
+        assert!(Section::ALL.iter().all(|s| options.includes(*s)));
+        assert!(!options.probes_public_ip());
+        assert!(!options.probes_bandwidth());
+        assert!(options.disk_sample_interval.is_zero());
+    }
+
+    #[test]
+    fn test_builder_selects_sections() {
+        let options = CollectOptions::empty()
+            .section(Section::Cpu)
+            .sections([Section::Memory, Section::Cpu]);
+
+        assert_eq!(options.sections, vec![Section::Cpu, Section::Memory]);
+        assert!(!options.includes(Section::Disks));
+
+        let options = options.without_section(Section::Cpu);
+        assert_eq!(options.sections, vec![Section::Memory]);
+    }
+
+    #[test]
+    fn test_probes_require_network_details() {
+        let options = CollectOptions::empty().network_probes(true);
+        assert!(!options.probes_public_ip());
+        assert!(!options.probes_bandwidth());
+
+        let options = options.section(Section::NetworkDetails).bandwidth(false);
+        assert!(options.probes_public_ip());
+        assert!(!options.probes_bandwidth());
+    }
+}
@@ -0,0 +1,283 @@
+use super::features::{CpuFeatures, PROC_CPUINFO};
+use super::topology::CpuTopology;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use sysinfo::System;
+
+/// CPU directory relative to the sysfs root
+pub const SYSFS_CPU_DIR: &str = "devices/system/cpu";
+
+/// Kernel/system statistics file holding cumulative CPU times
+pub const PROC_STAT: &str = "/proc/stat";
+
+/// CPU utilization, frequencies and load
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CpuDetails {
+    /// Utilization across all cores, in percent
+    pub global_usage: f32,
+    pub cores: Vec<CoreInfo>,
+    pub load_average: LoadAverage,
+    /// Share of CPU time per state over the sampling interval (Linux only)
+    pub times: Option<CpuTimes>,
+    /// Packages, cores, threads, caches and NUMA nodes (Linux only)
+    pub topology: Option<CpuTopology>,
+    /// Feature flags, microcode and vulnerability mitigations (Linux only)
+    pub features: Option<CpuFeatures>,
+}
+
+/// Per-logical-core utilization and frequency
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoreInfo {
+    pub name: String,
+    /// Utilization in percent
+    pub usage: f32,
+    /// Current frequency in MHz
+    pub frequency_mhz: u64,
+    /// Lowest frequency the core supports, in MHz
+    pub min_frequency_mhz: Option<u64>,
+    /// Highest frequency the core supports, in MHz
+    pub max_frequency_mhz: Option<u64>,
+}
+
+/// 1, 5 and 15 minute load averages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+/// Percentage of CPU time spent in each state between two samples
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuTimes {
+    pub user: f64,
+    pub nice: f64,
+    pub system: f64,
+    pub idle: f64,
+    pub iowait: f64,
+    pub irq: f64,
+    pub softirq: f64,
+    pub steal: f64,
+    pub guest: f64,
+    pub guest_nice: f64,
+    /// Length of the sampling interval in milliseconds
+    pub interval_ms: u64,
+}
+
+/// Cumulative CPU time counters (in clock ticks) from the `cpu` line of `/proc/stat`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CpuTimeSample {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+    pub guest: u64,
+    pub guest_nice: u64,
+}
+
+impl CpuTimeSample {
+    /// Read the aggregate CPU counters from a `/proc/stat` file
+    pub fn read(path: &Path) -> Option<Self> {
+        Self::parse(&std::fs::read_to_string(path).ok()?)
+    }
+
+    /// Parse the aggregate `cpu` line of `/proc/stat` contents
+    pub fn parse(contents: &str) -> Option<Self> {
+        let line = contents
+            .lines()
+            .find(|line| line.split_whitespace().next() == Some("cpu"))?;
+        let values: Vec<u64> = line
+            .split_whitespace()
+            .skip(1)
+            .map(|value| value.parse().unwrap_or(0))
+            .collect();
+        // Older kernels omit the trailing steal/guest columns
+        let field = |index: usize| values.get(index).copied().unwrap_or(0);
+        if values.len() < 4 {
+            return None;
+        }
+
+        Some(Self {
+            user: field(0),
+            nice: field(1),
+            system: field(2),
+            idle: field(3),
+            iowait: field(4),
+            irq: field(5),
+            softirq: field(6),
+            steal: field(7),
+            guest: field(8),
+            guest_nice: field(9),
+        })
+    }
+
+    /// Total elapsed ticks; guest time is already counted in user and nice
+    fn total(&self) -> u64 {
+        self.user
+            + self.nice
+            + self.system
+            + self.idle
+            + self.iowait
+            + self.irq
+            + self.softirq
+            + self.steal
+    }
+}
+
+impl CpuTimes {
+    /// Percentages of time spent in each state between two samples
+    pub fn between(earlier: &CpuTimeSample, later: &CpuTimeSample, interval_ms: u64) -> Self {
+        let total = later.total().saturating_sub(earlier.total());
+        let percent = |before: u64, after: u64| {
+            if total == 0 {
+                0.0
+            } else {
+                after.saturating_sub(before) as f64 / total as f64 * 100.0
+            }
+        };
+
+        Self {
+            user: percent(earlier.user, later.user),
+            nice: percent(earlier.nice, later.nice),
+            system: percent(earlier.system, later.system),
+            idle: percent(earlier.idle, later.idle),
+            iowait: percent(earlier.iowait, later.iowait),
+            irq: percent(earlier.irq, later.irq),
+            softirq: percent(earlier.softirq, later.softirq),
+            steal: percent(earlier.steal, later.steal),
+            guest: percent(earlier.guest, later.guest),
+            guest_nice: percent(earlier.guest_nice, later.guest_nice),
+            interval_ms,
+        }
+    }
+
+    /// State names and percentages, in `/proc/stat` column order
+    pub fn entries(&self) -> [(&'static str, f64); 10] {
+        [
+            ("user", self.user),
+            ("nice", self.nice),
+            ("system", self.system),
+            ("idle", self.idle),
+            ("iowait", self.iowait),
+            ("irq", self.irq),
+            ("softirq", self.softirq),
+            ("steal", self.steal),
+            ("guest", self.guest),
+            ("guest_nice", self.guest_nice),
+        ]
+    }
+}
+
+impl CpuDetails {
+    /// Build CPU details from a `System` whose CPUs have been refreshed twice,
+    /// reading frequency limits, topology and mitigations below `sysfs_root` (normally `/sys`)
+    pub fn from_system(sys: &System, sysfs_root: &Path, times: Option<CpuTimes>) -> Self {
+        let cores = sys
+            .cpus()
+            .iter()
+            .enumerate()
+            .map(|(index, cpu)| {
+                let (min_frequency_mhz, max_frequency_mhz) =
+                    read_frequency_limits(sysfs_root, index);
+                CoreInfo {
+                    name: cpu.name().to_string(),
+                    usage: cpu.cpu_usage(),
+                    frequency_mhz: cpu.frequency(),
+                    min_frequency_mhz,
+                    max_frequency_mhz,
+                }
+            })
+            .collect();
+
+        let load = System::load_average();
+
+        Self {
+            global_usage: sys.global_cpu_info().cpu_usage(),
+            cores,
+            load_average: LoadAverage {
+                one: load.one,
+                five: load.five,
+                fifteen: load.fifteen,
+            },
+            times,
+            topology: CpuTopology::read(sysfs_root),
+            features: CpuFeatures::read(Path::new(PROC_CPUINFO), sysfs_root),
+        }
+    }
+}
+
+/// Read `cpuinfo_min_freq`/`cpuinfo_max_freq` (kHz) for a core, in MHz
+pub fn read_frequency_limits(sysfs_root: &Path, index: usize) -> (Option<u64>, Option<u64>) {
+    let cpufreq = sysfs_root
+        .join(SYSFS_CPU_DIR)
+        .join(format!("cpu{}", index))
+        .join("cpufreq");
+    let read_mhz = |file: &str| {
+        std::fs::read_to_string(cpufreq.join(file))
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(|khz| khz / 1000)
+    };
+    (read_mhz("cpuinfo_min_freq"), read_mhz("cpuinfo_max_freq"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixture_path;
+
+    const PROC_STAT_BEFORE: &str = "cpu  1000 50 300 8000 100 10 20 30 0 0
+cpu0 500 25 150 4000 50 5 10 15 0 0
+intr 12345
+ctxt 67890
+";
+
+    const PROC_STAT_AFTER: &str = "cpu  1200 50 400 8500 150 40 40 130 80 0
+cpu0 600 25 200 4250 75 5 20 65 40 0
+";
+
+    #[test]
+    fn test_parse_proc_stat() {
+        let sample = CpuTimeSample::parse(PROC_STAT_BEFORE).unwrap();
+        assert_eq!(sample.user, 1000);
+        assert_eq!(sample.idle, 8000);
+        assert_eq!(sample.steal, 30);
+        assert!(CpuTimeSample::parse("intr 1 2 3\n").is_none());
+
+        let old_kernel = CpuTimeSample::parse("cpu 10 20 30 40\n").unwrap();
+        assert_eq!(old_kernel.idle, 40);
+        assert_eq!(old_kernel.steal, 0);
+    }
+
+    #[test]
+    fn test_cpu_times_between_samples() {
+        let before = CpuTimeSample::parse(PROC_STAT_BEFORE).unwrap();
+        let after = CpuTimeSample::parse(PROC_STAT_AFTER).unwrap();
+        let times = CpuTimes::between(&before, &after, 1000);
+
+        // 1000 ticks elapsed in total
+        assert_eq!(times.user, 20.0);
+        assert_eq!(times.system, 10.0);
+        assert_eq!(times.idle, 50.0);
+        assert_eq!(times.iowait, 5.0);
+        assert_eq!(times.irq, 3.0);
+        assert_eq!(times.softirq, 2.0);
+        assert_eq!(times.steal, 10.0);
+        assert_eq!(times.guest, 8.0);
+        assert_eq!(times.interval_ms, 1000);
+
+        let idle = CpuTimes::between(&before, &before, 0);
+        assert_eq!(idle.user, 0.0);
+    }
+
+    #[test]
+    fn test_read_frequency_limits() {
+        let root = fixture_path("sysfs");
+        assert_eq!(read_frequency_limits(&root, 1), (Some(800), Some(3600)));
+        assert_eq!(read_frequency_limits(&root, 7), (None, None));
+    }
+}
@@ -0,0 +1,250 @@
+use super::{counter_delta, SystemInfo, SECTOR_SIZE};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Per-device block I/O counters
+pub const PROC_DISKSTATS: &str = "/proc/diskstats";
+
+/// Cumulative I/O counters of one block device from `/proc/diskstats`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiskStatsSample {
+    pub device: String,
+    pub reads_completed: u64,
+    pub sectors_read: u64,
+    /// Milliseconds spent on reads
+    pub read_time_ms: u64,
+    pub writes_completed: u64,
+    pub sectors_written: u64,
+    /// Milliseconds spent on writes
+    pub write_time_ms: u64,
+    /// Requests currently issued to the device
+    pub in_flight: u64,
+    /// Milliseconds the device had at least one request in flight
+    pub io_time_ms: u64,
+    /// `io_time_ms` weighted by the number of requests in flight
+    pub weighted_io_time_ms: u64,
+}
+
+/// I/O rates of one block device between two samples, like `iostat -x`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiskIoStats {
+    pub device: String,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    pub reads_per_sec: f64,
+    pub writes_per_sec: f64,
+    /// Average time a request took, including queueing, in milliseconds
+    pub await_ms: f64,
+    /// Average number of requests queued or in flight
+    pub queue_depth: f64,
+    /// Share of time the device was busy, in percent
+    pub utilization_percent: f64,
+    /// Time between the two samples
+    pub interval_ms: u64,
+}
+
+impl DiskStatsSample {
+    /// Read every device from a `/proc/diskstats` file
+    pub fn read(path: &Path) -> Vec<Self> {
+        std::fs::read_to_string(path)
+            .map(|contents| Self::parse(&contents))
+            .unwrap_or_default()
+    }
+
+    /// Parse `/proc/diskstats` contents
+    pub fn parse(contents: &str) -> Vec<Self> {
+        contents
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                // major minor name, then at least the 11 counters of Linux 2.6
+                let device = fields.get(2)?.to_string();
+                let counters: Vec<u64> = fields
+                    .get(3..14)?
+                    .iter()
+                    .map(|value| value.parse().ok())
+                    .collect::<Option<_>>()?;
+
+                Some(Self {
+                    device,
+                    reads_completed: counters[0],
+                    sectors_read: counters[2],
+                    read_time_ms: counters[3],
+                    writes_completed: counters[4],
+                    sectors_written: counters[6],
+                    write_time_ms: counters[7],
+                    in_flight: counters[8],
+                    io_time_ms: counters[9],
+                    weighted_io_time_ms: counters[10],
+                })
+            })
+            .collect()
+    }
+
+    /// Whether the device has completed any I/O since boot
+    fn is_active(&self) -> bool {
+        self.reads_completed > 0 || self.writes_completed > 0
+    }
+}
+
+impl DiskIoStats {
+    /// Rates for devices present in both samples, skipping devices that never did I/O
+    pub fn between(
+        earlier: &[DiskStatsSample],
+        later: &[DiskStatsSample],
+        interval_ms: u64,
+    ) -> Vec<Self> {
+        let mut stats: Vec<Self> = later
+            .iter()
+            .filter(|after| after.is_active())
+            .filter_map(|after| {
+                let before = earlier
+                    .iter()
+                    .find(|before| before.device == after.device)?;
+                Some(Self::from_samples(before, after, interval_ms))
+            })
+            .collect();
+        stats.sort_by(|a, b| a.device.cmp(&b.device));
+        stats
+    }
+
+    fn from_samples(before: &DiskStatsSample, after: &DiskStatsSample, interval_ms: u64) -> Self {
+        let delta = |field: fn(&DiskStatsSample) -> u64| counter_delta(field(before), field(after));
+        let reads = delta(|s| s.reads_completed);
+        let writes = delta(|s| s.writes_completed);
+        let request_time = delta(|s| s.read_time_ms) + delta(|s| s.write_time_ms);
+        let seconds = interval_ms as f64 / 1000.0;
+        let per_second = |value: u64| {
+            if interval_ms == 0 {
+                0.0
+            } else {
+                value as f64 / seconds
+            }
+        };
+        let per_ms = |value: u64| {
+            if interval_ms == 0 {
+                0.0
+            } else {
+                value as f64 / interval_ms as f64
+            }
+        };
+
+        Self {
+            device: after.device.clone(),
+            read_bytes_per_sec: per_second(delta(|s| s.sectors_read) * SECTOR_SIZE),
+            write_bytes_per_sec: per_second(delta(|s| s.sectors_written) * SECTOR_SIZE),
+            reads_per_sec: per_second(reads),
+            writes_per_sec: per_second(writes),
+            await_ms: if reads + writes == 0 {
+                0.0
+            } else {
+                request_time as f64 / (reads + writes) as f64
+            },
+            queue_depth: per_ms(delta(|s| s.weighted_io_time_ms)),
+            utilization_percent: (per_ms(delta(|s| s.io_time_ms)) * 100.0).min(100.0),
+            interval_ms,
+        }
+    }
+
+    /// `iostat`-style table with a header row, one row per device
+    pub fn table_lines(stats: &[Self]) -> Vec<String> {
+        let mut lines = vec![format!(
+            "{:<12} {:>12} {:>12} {:>8} {:>8} {:>9} {:>7} {:>6}",
+            "Device", "Read/s", "Write/s", "r/s", "w/s", "await", "aqu-sz", "util"
+        )];
+        lines.extend(stats.iter().map(|stat| {
+            format!(
+                "{:<12} {:>12} {:>12} {:>8.1} {:>8.1} {:>6.2} ms {:>7.2} {:>5.1}%",
+                stat.device,
+                format_rate(stat.read_bytes_per_sec),
+                format_rate(stat.write_bytes_per_sec),
+                stat.reads_per_sec,
+                stat.writes_per_sec,
+                stat.await_ms,
+                stat.queue_depth,
+                stat.utilization_percent
+            )
+        }));
+        lines
+    }
+}
+
+/// Byte rate such as `1.50 MB/s`
+fn format_rate(bytes_per_sec: f64) -> String {
+    format!(
+        "{}/s",
+        SystemInfo::format_bytes(bytes_per_sec.round() as u64)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISKSTATS_BEFORE: &str = "\
+   7       0 loop0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+ 259       0 nvme0n1 1000 10 80000 2000 500 20 40000 3000 0 1500 5000 0 0 0 0 0 0
+ 259       1 nvme0n1p1 900 10 70000 1800 400 20 30000 2500 0 1400 4300 0 0 0 0 0 0
+   8       0 sda 50 0 400 100 0 0 0 0 0 80 100
+";
+
+    const DISKSTATS_AFTER: &str = "\
+   7       0 loop0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0
+ 259       0 nvme0n1 1100 10 84096 2150 700 20 48192 3450 2 1750 5900 0 0 0 0 0 0
+ 259       1 nvme0n1p1 1000 10 74096 1950 600 20 38192 2950 2 1650 5200 0 0 0 0 0 0
+   8       0 sda 50 0 400 100 0 0 0 0 0 80 100
+";
+
+    #[test]
+    fn test_parse_diskstats() {
+        let samples = DiskStatsSample::parse(DISKSTATS_BEFORE);
+        assert_eq!(samples.len(), 4);
+        assert_eq!(samples[1].device, "nvme0n1");
+        assert_eq!(samples[1].sectors_read, 80_000);
+        assert_eq!(samples[1].write_time_ms, 3000);
+        assert_eq!(samples[1].weighted_io_time_ms, 5000);
+        // Linux 2.6 layout without discard and flush columns
+        assert_eq!(samples[3].io_time_ms, 80);
+
+        assert!(DiskStatsSample::parse("   8 0 sda 1 2 3\n").is_empty());
+    }
+
+    #[test]
+    fn test_io_stats_between_samples() {
+        let before = DiskStatsSample::parse(DISKSTATS_BEFORE);
+        let after = DiskStatsSample::parse(DISKSTATS_AFTER);
+        let stats = DiskIoStats::between(&before, &after, 1000);
+
+        // loop0 never did any I/O
+        let devices: Vec<&str> = stats.iter().map(|s| s.device.as_str()).collect();
+        assert_eq!(devices, ["nvme0n1", "nvme0n1p1", "sda"]);
+
+        let nvme = &stats[0];
+        assert_eq!(nvme.read_bytes_per_sec, 4096.0 * 512.0);
+        assert_eq!(nvme.write_bytes_per_sec, 8192.0 * 512.0);
+        assert_eq!(nvme.reads_per_sec, 100.0);
+        assert_eq!(nvme.writes_per_sec, 200.0);
+        // 150 ms reading + 450 ms writing over 300 requests
+        assert_eq!(nvme.await_ms, 2.0);
+        assert_eq!(nvme.queue_depth, 0.9);
+        assert_eq!(nvme.utilization_percent, 25.0);
+
+        let idle = &stats[2];
+        assert_eq!(idle.reads_per_sec, 0.0);
+        assert_eq!(idle.await_ms, 0.0);
+        assert_eq!(idle.utilization_percent, 0.0);
+    }
+
+    #[test]
+    fn test_table_lines() {
+        let before = DiskStatsSample::parse(DISKSTATS_BEFORE);
+        let after = DiskStatsSample::parse(DISKSTATS_AFTER);
+        let lines = DiskIoStats::table_lines(&DiskIoStats::between(&before, &after, 1000));
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("Device"));
+        assert_eq!(
+            lines[1],
+            "nvme0n1         2.00 MB/s    4.00 MB/s    100.0    200.0   2.00 ms    0.90  25.0%"
+        );
+    }
+}
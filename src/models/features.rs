@@ -0,0 +1,266 @@
+use super::cpu::SYSFS_CPU_DIR;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Kernel CPU description file holding feature flags and microcode revision
+pub const PROC_CPUINFO: &str = "/proc/cpuinfo";
+
+/// Instruction set extensions worth calling out, as (cpuinfo flag, display name)
+pub const NOTABLE_FLAGS: &[(&str, &str)] = &[
+    ("sse4_2", "SSE4.2"),
+    ("popcnt", "POPCNT"),
+    ("avx", "AVX"),
+    ("avx2", "AVX2"),
+    ("fma", "FMA"),
+    ("bmi2", "BMI2"),
+    ("avx512f", "AVX-512F"),
+    ("avx512bw", "AVX-512BW"),
+    ("avx512vl", "AVX-512VL"),
+    ("avx512_vnni", "AVX-512 VNNI"),
+    ("aes", "AES-NI"),
+    ("sha_ni", "SHA-NI"),
+    ("vmx", "VT-x"),
+    ("svm", "AMD-V"),
+    ("asimd", "NEON"),
+    ("sve", "SVE"),
+];
+
+/// Instruction set flags, microcode and speculative execution mitigations
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuFeatures {
+    /// Feature flags of the first CPU, sorted
+    pub flags: Vec<String>,
+    /// Microcode revision as reported by the kernel, e.g. `0xf0`
+    pub microcode: Option<String>,
+    pub vulnerabilities: Vec<CpuVulnerability>,
+}
+
+/// Kernel status for one hardware vulnerability
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CpuVulnerability {
+    /// File name below `vulnerabilities/`, e.g. `spectre_v2`
+    pub name: String,
+    pub state: VulnerabilityState,
+    /// Full status line, e.g. `Mitigation: Retpolines; IBPB: conditional`
+    pub status: String,
+}
+
+/// Classification of a vulnerability status line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VulnerabilityState {
+    NotAffected,
+    Mitigated,
+    Vulnerable,
+    Unknown,
+}
+
+impl VulnerabilityState {
+    /// Classify a status line from `/sys/devices/system/cpu/vulnerabilities/*`
+    pub fn from_status(status: &str) -> Self {
+        if status.starts_with("Not affected") {
+            Self::NotAffected
+        } else if status.starts_with("Mitigation") {
+            Self::Mitigated
+        } else if status.starts_with("Vulnerable") {
+            Self::Vulnerable
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+impl CpuFeatures {
+    /// Read flags and microcode from `cpuinfo` and mitigations below `sysfs_root`
+    ///
+    /// Returns `None` when neither source is available.
+    pub fn read(cpuinfo: &Path, sysfs_root: &Path) -> Option<Self> {
+        let (flags, microcode) = std::fs::read_to_string(cpuinfo)
+            .map(|contents| Self::parse_cpuinfo(&contents))
+            .unwrap_or_default();
+        let vulnerabilities =
+            read_vulnerabilities(&sysfs_root.join(SYSFS_CPU_DIR).join("vulnerabilities"));
+
+        if flags.is_empty() && microcode.is_none() && vulnerabilities.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            flags,
+            microcode,
+            vulnerabilities,
+        })
+    }
+
+    /// Extract the flags and microcode revision of the first CPU in `/proc/cpuinfo`
+    ///
+    /// x86 lists flags under `flags`, ARM under `Features`.
+    pub fn parse_cpuinfo(contents: &str) -> (Vec<String>, Option<String>) {
+        let mut flags = Vec::new();
+        let mut microcode = None;
+
+        // Only the first processor block; all CPUs report the same flags
+        for line in contents.lines().take_while(|line| !line.trim().is_empty()) {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            match key.trim() {
+                "flags" | "Features" => {
+                    flags = value.split_whitespace().map(str::to_string).collect();
+                }
+                "microcode" => microcode = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+
+        flags.sort();
+        flags.dedup();
+        (flags, microcode)
+    }
+
+    /// Whether the CPU reports `flag`
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags
+            .binary_search_by(|f| f.as_str().cmp(flag))
+            .is_ok()
+    }
+
+    /// Support for each of the [`NOTABLE_FLAGS`], by display name
+    pub fn instruction_sets(&self) -> Vec<(&'static str, bool)> {
+        NOTABLE_FLAGS
+            .iter()
+            .map(|(flag, name)| (*name, self.has_flag(flag)))
+            .collect()
+    }
+
+    /// Vulnerabilities the kernel reports as unmitigated
+    pub fn unmitigated(&self) -> impl Iterator<Item = &CpuVulnerability> {
+        self.vulnerabilities
+            .iter()
+            .filter(|v| v.state == VulnerabilityState::Vulnerable)
+    }
+}
+
+fn read_vulnerabilities(dir: &Path) -> Vec<CpuVulnerability> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut vulnerabilities: Vec<CpuVulnerability> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let status = std::fs::read_to_string(entry.path())
+                .ok()?
+                .trim()
+                .to_string();
+            Some(CpuVulnerability {
+                name: entry.file_name().to_str()?.to_string(),
+                state: VulnerabilityState::from_status(&status),
+                status,
+            })
+        })
+        .collect();
+    vulnerabilities.sort_by(|a, b| a.name.cmp(&b.name));
+    vulnerabilities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixture_path;
+
+    const CPUINFO_X86: &str = "processor\t: 0
+vendor_id\t: GenuineIntel
+model name\t: Intel(R) Xeon(R) CPU
+microcode\t: 0xf0
+flags\t\t: fpu sse2 sse4_2 avx avx2 aes avx512f vmx
+bugs\t\t: spectre_v1 spectre_v2
+
+processor\t: 1
+flags\t\t: fpu
+";
+
+    const CPUINFO_ARM: &str = "processor\t: 0
+BogoMIPS\t: 48.00
+Features\t: fp asimd evtstrm aes pmull sha1 sha2 crc32
+CPU implementer\t: 0x41
+";
+
+    #[test]
+    fn test_parse_x86_cpuinfo() {
+        let (flags, microcode) = CpuFeatures::parse_cpuinfo(CPUINFO_X86);
+        assert_eq!(microcode.as_deref(), Some("0xf0"));
+        assert_eq!(flags.len(), 8);
+
+        let features = CpuFeatures {
+            flags,
+            ..CpuFeatures::default()
+        };
+        assert!(features.has_flag("avx2"));
+        assert!(!features.has_flag("sha_ni"));
+
+        let sets = features.instruction_sets();
+        assert!(sets.contains(&("AVX-512F", true)));
+        assert!(sets.contains(&("AES-NI", true)));
+        assert!(sets.contains(&("SHA-NI", false)));
+    }
+
+    #[test]
+    fn test_parse_arm_cpuinfo() {
+        let (flags, microcode) = CpuFeatures::parse_cpuinfo(CPUINFO_ARM);
+        assert!(microcode.is_none());
+        let features = CpuFeatures {
+            flags,
+            ..CpuFeatures::default()
+        };
+        assert!(features.instruction_sets().contains(&("NEON", true)));
+    }
+
+    #[test]
+    fn test_vulnerability_state() {
+        assert_eq!(
+            VulnerabilityState::from_status("Not affected"),
+            VulnerabilityState::NotAffected
+        );
+        assert_eq!(
+            VulnerabilityState::from_status("Mitigation: PTI"),
+            VulnerabilityState::Mitigated
+        );
+        assert_eq!(
+            VulnerabilityState::from_status(
+                "Vulnerable: Clear CPU buffers attempted, no microcode"
+            ),
+            VulnerabilityState::Vulnerable
+        );
+        assert_eq!(
+            VulnerabilityState::from_status("Unknown: No mitigations"),
+            VulnerabilityState::Unknown
+        );
+    }
+
+    #[test]
+    fn test_read_fixture_vulnerabilities() {
+        let features =
+            CpuFeatures::read(Path::new("/nonexistent/cpuinfo"), &fixture_path("sysfs")).unwrap();
+        assert!(features.flags.is_empty());
+
+        let names: Vec<&str> = features
+            .vulnerabilities
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["mds", "meltdown", "spectre_v1", "spectre_v2"]);
+        assert_eq!(
+            features.vulnerabilities[3].status,
+            "Mitigation: Retpolines; IBPB: conditional"
+        );
+
+        let unmitigated: Vec<&str> = features.unmitigated().map(|v| v.name.as_str()).collect();
+        assert_eq!(unmitigated, vec!["mds"]);
+    }
+
+    #[test]
+    fn test_missing_sources() {
+        assert!(CpuFeatures::read(Path::new("/nonexistent"), Path::new("/nonexistent")).is_none());
+    }
+}
@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Filesystem types that hold no user data and are hidden unless asked for
+pub const PSEUDO_FS_TYPES: &[&str] = &[
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "devtmpfs",
+    "efivarfs",
+    "fusectl",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "overlay",
+    "proc",
+    "pstore",
+    "ramfs",
+    "rpc_pipefs",
+    "securityfs",
+    "selinuxfs",
+    "squashfs",
+    "sysfs",
+    "tmpfs",
+    "tracefs",
+    "fuse.lxcfs",
+    "fuse.portal",
+    "fuse.gvfsd-fuse",
+    "fuse.snapfuse",
+];
+
+/// Which mounted filesystems to report
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiskFilter {
+    /// Only these filesystem types, when not empty; listed pseudo types are shown
+    pub fs_types: Vec<String>,
+    /// Never these filesystem types
+    pub exclude_fs_types: Vec<String>,
+    /// Only mount points matching one of these globs (`*`, `?`), when not empty
+    pub mounts: Vec<String>,
+    /// Also show pseudo filesystems such as tmpfs, overlay and squashfs
+    pub include_pseudo: bool,
+}
+
+/// Inode counts of a mounted filesystem
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InodeUsage {
+    pub total: u64,
+    pub used: u64,
+    pub free: u64,
+}
+
+impl InodeUsage {
+    /// Query the filesystem mounted at `mount_point` with `statvfs`
+    ///
+    /// Returns `None` when the call fails or the filesystem has no fixed
+    /// inode table (btrfs, vfat and most pseudo filesystems report zero).
+    #[cfg(unix)]
+    pub fn read(mount_point: &Path) -> Option<Self> {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = CString::new(mount_point.as_os_str().as_bytes()).ok()?;
+        let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+        // SAFETY: `path` is NUL-terminated and `stat` is only read after a successful call
+        let stat = unsafe {
+            if libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) != 0 {
+                return None;
+            }
+            stat.assume_init()
+        };
+        #[allow(clippy::unnecessary_cast)]
+        Self::from_counts(stat.f_files as u64, stat.f_ffree as u64)
+    }
+
+    #[cfg(not(unix))]
+    pub fn read(_mount_point: &Path) -> Option<Self> {
+        None
+    }
+
+    /// Build from total and free counts; `None` when the total is zero
+    pub fn from_counts(total: u64, free: u64) -> Option<Self> {
+        (total > 0).then(|| Self {
+            total,
+            used: total.saturating_sub(free),
+            free: free.min(total),
+        })
+    }
+
+    /// Share of inodes in use, in percent
+    pub fn usage_percent(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.used as f64 / self.total as f64 * 100.0
+        }
+    }
+}
+
+impl DiskFilter {
+    /// Whether a filesystem of `fs_type` mounted at `mount_point` is reported
+    pub fn matches(&self, fs_type: &str, mount_point: &str) -> bool {
+        let listed = |types: &[String]| types.iter().any(|t| t == fs_type);
+        if !self.fs_types.is_empty() && !listed(&self.fs_types) {
+            return false;
+        }
+        if listed(&self.exclude_fs_types) {
+            return false;
+        }
+        if !self.mounts.is_empty() && !self.mounts.iter().any(|glob| glob_match(glob, mount_point))
+        {
+            return false;
+        }
+        self.include_pseudo || listed(&self.fs_types) || !is_pseudo_fs(fs_type)
+    }
+}
+
+pub fn is_pseudo_fs(fs_type: &str) -> bool {
+    PSEUDO_FS_TYPES.contains(&fs_type)
+}
+
+/// Shell-style glob match where `*` matches any run of characters, `/` included
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it is currently matched up to
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("/", "/"));
+        assert!(glob_match("/home*", "/home"));
+        assert!(glob_match("/mnt/*", "/mnt/data/backups"));
+        assert!(glob_match("/media/usb?stick", "/media/usb stick"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("/*/log", "/var/log"));
+        assert!(!glob_match("/*/log", "/var/lib"));
+        assert!(!glob_match("/boot", "/boot/efi"));
+    }
+
+    #[test]
+    fn test_disk_filter_hides_pseudo_by_default() {
+        let filter = DiskFilter::default();
+        assert!(filter.matches("ext4", "/"));
+        assert!(!filter.matches("tmpfs", "/run"));
+        assert!(!filter.matches("squashfs", "/snap/core22/1380"));
+        assert!(!filter.matches("overlay", "/var/lib/docker/overlay2/abc/merged"));
+
+        let all = DiskFilter {
+            include_pseudo: true,
+            ..DiskFilter::default()
+        };
+        assert!(all.matches("tmpfs", "/run"));
+    }
+
+    #[test]
+    fn test_disk_filter_types_and_mounts() {
+        let tmpfs_only = DiskFilter {
+            fs_types: vec!["tmpfs".to_string()],
+            ..DiskFilter::default()
+        };
+        assert!(tmpfs_only.matches("tmpfs", "/run"));
+        assert!(!tmpfs_only.matches("ext4", "/"));
+
+        let filter = DiskFilter {
+            exclude_fs_types: vec!["vfat".to_string()],
+            mounts: vec!["/".to_string(), "/boot*".to_string()],
+            ..DiskFilter::default()
+        };
+        assert!(filter.matches("ext4", "/"));
+        assert!(filter.matches("ext4", "/boot"));
+        assert!(!filter.matches("vfat", "/boot/efi"));
+        assert!(!filter.matches("xfs", "/srv"));
+    }
+
+    #[test]
+    fn test_from_counts() {
+        let inodes = InodeUsage::from_counts(1000, 250).unwrap();
+        assert_eq!(inodes.used, 750);
+        assert_eq!(inodes.usage_percent(), 75.0);
+
+        assert!(InodeUsage::from_counts(0, 0).is_none());
+        assert_eq!(InodeUsage::from_counts(10, 20).unwrap().used, 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_root_filesystem() {
+        // Any real filesystem answers statvfs; inode-less ones yield None
+        if let Some(inodes) = InodeUsage::read(Path::new("/")) {
+            assert_eq!(inodes.used + inodes.free, inodes.total);
+        }
+        assert!(InodeUsage::read(Path::new("/nonexistent/mount")).is_none());
+    }
+}
@@ -0,0 +1,404 @@
+use super::{DiskInfo, SystemInfo};
+use crate::Result;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Name of the history file inside the state directory
+const HISTORY_FILE_NAME: &str = "disk-history.json";
+
+/// Samples closer together than this are not recorded
+pub const MIN_SAMPLE_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Samples needed before a forecast is made
+const MIN_SAMPLES: usize = 3;
+
+/// Time the samples must span before a forecast is made
+const MIN_SPAN: Duration = Duration::from_secs(30 * 60);
+
+/// Samples fitted at most; longer histories are thinned evenly
+const MAX_FIT_SAMPLES: usize = 500;
+
+/// One reading of a filesystem's free space
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiskSample {
+    /// Unix time in seconds
+    pub timestamp: u64,
+    pub available_space: u64,
+    pub total_space: u64,
+}
+
+/// Free space samples per mount point, persisted between runs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiskHistory {
+    /// Samples oldest first, keyed by mount point
+    pub mounts: BTreeMap<String, Vec<DiskSample>>,
+}
+
+/// Fill rate and time until full of a filesystem, fitted to its history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiskForecast {
+    /// Bytes consumed per second; negative while space is being freed
+    pub fill_rate_bytes_per_sec: f64,
+    /// `None` unless the filesystem is filling up
+    pub seconds_until_full: Option<u64>,
+    /// Samples the forecast is based on
+    pub samples: usize,
+    /// Time between the oldest and newest sample, in seconds
+    pub span_secs: u64,
+}
+
+impl DiskHistory {
+    /// Default history location: `$XDG_STATE_HOME/system-index/disk-history.json`,
+    /// falling back to `~/.local/state/system-index/disk-history.json`
+    pub fn default_path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_STATE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| Path::new(&home).join(".local").join("state"))
+            })?;
+        Some(base.join("system-index").join(HISTORY_FILE_NAME))
+    }
+
+    /// Load a history file; a missing file is an empty history
+    pub fn load(path: &Path) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .with_context(|| format!("invalid disk history {}", path.display())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err).with_context(|| format!("failed to read {}", path.display())),
+        }
+    }
+
+    /// Write the history, replacing the file atomically
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        // Per-process temporary name so concurrent runs never write the same file
+        let temp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        std::fs::write(&temp, serde_json::to_vec(self)?)
+            .with_context(|| format!("failed to write {}", temp.display()))?;
+        std::fs::rename(&temp, path)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Add a sample per disk and drop samples older than `window`
+    ///
+    /// Disks sampled less than [`MIN_SAMPLE_INTERVAL`] ago are skipped, and a
+    /// disk whose size changed starts over. Returns whether anything changed.
+    pub fn record(&mut self, disks: &[DiskInfo], now: u64, window: Duration) -> bool {
+        let mut changed = false;
+        for disk in disks {
+            let samples = self.mounts.entry(disk.mount_point.clone()).or_default();
+            if let Some(last) = samples.last() {
+                if last.total_space != disk.total_space {
+                    samples.clear();
+                } else if now.saturating_sub(last.timestamp) < MIN_SAMPLE_INTERVAL.as_secs() {
+                    continue;
+                }
+            }
+            samples.push(DiskSample {
+                timestamp: now,
+                available_space: disk.available_space,
+                total_space: disk.total_space,
+            });
+            changed = true;
+        }
+
+        let cutoff = now.saturating_sub(window.as_secs());
+        for samples in self.mounts.values_mut() {
+            let before = samples.len();
+            samples.retain(|sample| sample.timestamp >= cutoff);
+            changed |= samples.len() != before;
+        }
+        let before = self.mounts.len();
+        self.mounts.retain(|_, samples| !samples.is_empty());
+        changed || self.mounts.len() != before
+    }
+
+    /// Forecast for a mount point; `None` until enough history is recorded
+    pub fn forecast(&self, mount_point: &str, now: u64) -> Option<DiskForecast> {
+        DiskForecast::fit(self.mounts.get(mount_point)?, now)
+    }
+
+    /// Set the forecast of each disk from this history
+    pub fn apply(&self, disks: &mut [DiskInfo], now: u64) {
+        for disk in disks {
+            disk.forecast = self.forecast(&disk.mount_point, now);
+        }
+    }
+
+    /// Load, record `disks`, save and apply forecasts in one go
+    ///
+    /// Loading right before recording keeps samples written by other runs
+    /// since this process last looked at the file.
+    pub fn update(path: &Path, disks: &mut [DiskInfo], window: Duration) -> Result<()> {
+        let now = unix_now();
+        let mut history = Self::load(path)?;
+        if history.record(disks, now, window) {
+            history.save(path)?;
+        }
+        history.apply(disks, now);
+        Ok(())
+    }
+}
+
+impl DiskForecast {
+    /// Fit a Theil–Sen line to free space over time
+    ///
+    /// The median of pairwise slopes ignores one-off spikes such as a large
+    /// file written and deleted again, which would skew least squares.
+    pub fn fit(samples: &[DiskSample], now: u64) -> Option<Self> {
+        let (first, last) = (samples.first()?, samples.last()?);
+        let span_secs = last.timestamp.saturating_sub(first.timestamp);
+        if samples.len() < MIN_SAMPLES || span_secs < MIN_SPAN.as_secs() {
+            return None;
+        }
+
+        let step = samples.len().div_ceil(MAX_FIT_SAMPLES);
+        let points: Vec<(f64, f64)> = samples
+            .iter()
+            .rev()
+            .step_by(step)
+            .map(|s| {
+                (
+                    s.timestamp.saturating_sub(first.timestamp) as f64,
+                    s.available_space as f64,
+                )
+            })
+            .collect();
+
+        let mut slopes = Vec::with_capacity(points.len() * points.len() / 2);
+        for (i, &(x1, y1)) in points.iter().enumerate() {
+            for &(x2, y2) in &points[i + 1..] {
+                if x1 != x2 {
+                    slopes.push((y2 - y1) / (x2 - x1));
+                }
+            }
+        }
+        let slope = median(&mut slopes)?;
+        let mut intercepts: Vec<f64> = points.iter().map(|&(x, y)| y - slope * x).collect();
+        let intercept = median(&mut intercepts)?;
+
+        // Free space reaches zero at `-intercept / slope` seconds after the first sample
+        let seconds_until_full = (slope < 0.0).then(|| {
+            let full_at = first.timestamp as f64 - intercept / slope;
+            (full_at - now as f64).max(0.0).round() as u64
+        });
+
+        Some(Self {
+            fill_rate_bytes_per_sec: -slope,
+            seconds_until_full,
+            samples: samples.len(),
+            span_secs,
+        })
+    }
+
+    /// Whether the filesystem is expected to be full within `duration`
+    pub fn full_within(&self, duration: Duration) -> bool {
+        self.seconds_until_full
+            .is_some_and(|secs| secs <= duration.as_secs())
+    }
+
+    /// e.g. `filling at 1.20 GB/day, full in 3d 4h 0m 0s (12 samples over 6h 0m 0s)`
+    pub fn summary(&self) -> String {
+        let per_day = self.fill_rate_bytes_per_sec * 86400.0;
+        let trend = match self.seconds_until_full {
+            Some(secs) => format!(
+                "filling at {}/day, full in {}",
+                SystemInfo::format_bytes(per_day as u64),
+                SystemInfo::format_uptime(secs)
+            ),
+            None if per_day < 0.0 => format!(
+                "not filling, {}/day freed",
+                SystemInfo::format_bytes(-per_day as u64)
+            ),
+            None => "not filling".to_string(),
+        };
+        format!(
+            "{} ({} samples over {})",
+            trend,
+            self.samples,
+            SystemInfo::format_uptime(self.span_secs)
+        )
+    }
+}
+
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GB: u64 = 1024 * 1024 * 1024;
+    const HOUR: u64 = 3600;
+
+    fn sample(timestamp: u64, available_space: u64) -> DiskSample {
+        DiskSample {
+            timestamp,
+            available_space,
+            total_space: 100 * GB,
+        }
+    }
+
+    fn disk(mount_point: &str, available_space: u64, total_space: u64) -> DiskInfo {
+        DiskInfo {
+            name: "/dev/vda".to_string(),
+            mount_point: mount_point.to_string(),
+            total_space,
+            available_space,
+            file_system: "ext4".to_string(),
+            inodes: None,
+            dev: None,
+            source: None,
+            mount_options: Vec::new(),
+            forecast: None,
+        }
+    }
+
+    #[test]
+    fn test_linear_fill() {
+        // 1 GB consumed per hour with 10 GB left at the last sample
+        let samples: Vec<DiskSample> = (0..10)
+            .map(|hour| sample(hour * HOUR, (19 - hour) * GB))
+            .collect();
+        let forecast = DiskForecast::fit(&samples, 9 * HOUR).unwrap();
+        assert_eq!(forecast.fill_rate_bytes_per_sec, GB as f64 / HOUR as f64);
+        assert_eq!(forecast.seconds_until_full, Some(10 * HOUR));
+        assert!(forecast.full_within(Duration::from_secs(48 * HOUR)));
+        assert!(!forecast.full_within(Duration::from_secs(HOUR)));
+        assert_eq!(
+            forecast.summary(),
+            "filling at 24.00 GB/day, full in 10h 0m 0s (10 samples over 9h 0m 0s)"
+        );
+
+        // Time since the last sample counts against the remaining time
+        let later = DiskForecast::fit(&samples, 12 * HOUR).unwrap();
+        assert_eq!(later.seconds_until_full, Some(7 * HOUR));
+    }
+
+    #[test]
+    fn test_robust_to_outliers() {
+        let mut samples: Vec<DiskSample> = (0..10)
+            .map(|hour| sample(hour * HOUR, (19 - hour) * GB))
+            .collect();
+        // A 15 GB file written and removed an hour later
+        samples[4].available_space = GB;
+        let forecast = DiskForecast::fit(&samples, 9 * HOUR).unwrap();
+        assert_eq!(forecast.seconds_until_full, Some(10 * HOUR));
+    }
+
+    #[test]
+    fn test_not_filling() {
+        let freeing: Vec<DiskSample> = (0..4)
+            .map(|hour| sample(hour * HOUR, (10 + hour) * GB))
+            .collect();
+        let forecast = DiskForecast::fit(&freeing, 3 * HOUR).unwrap();
+        assert!(forecast.seconds_until_full.is_none());
+        assert!(forecast
+            .summary()
+            .starts_with("not filling, 24.00 GB/day freed"));
+
+        let flat: Vec<DiskSample> = (0..4).map(|hour| sample(hour * HOUR, 10 * GB)).collect();
+        assert_eq!(
+            DiskForecast::fit(&flat, 3 * HOUR).unwrap().summary(),
+            "not filling (4 samples over 3h 0m 0s)"
+        );
+    }
+
+    #[test]
+    fn test_needs_enough_history() {
+        assert!(DiskForecast::fit(&[], 0).is_none());
+        let two = [sample(0, 10 * GB), sample(HOUR, 9 * GB)];
+        assert!(DiskForecast::fit(&two, HOUR).is_none());
+        let short: Vec<DiskSample> = (0..5).map(|i| sample(i * 60, 10 * GB - i)).collect();
+        assert!(DiskForecast::fit(&short, 300).is_none());
+    }
+
+    #[test]
+    fn test_record_interval_window_and_resize() {
+        let window = Duration::from_secs(24 * HOUR);
+        let mut history = DiskHistory::default();
+        assert!(history.record(&[disk("/", 50 * GB, 100 * GB)], 0, window));
+        // Too soon after the previous sample
+        assert!(!history.record(&[disk("/", 49 * GB, 100 * GB)], 60, window));
+        assert!(history.record(&[disk("/", 48 * GB, 100 * GB)], HOUR, window));
+        assert_eq!(history.mounts["/"].len(), 2);
+
+        // Samples older than the window are dropped, and so are mounts without samples
+        history.record(&[disk("/data", GB, 10 * GB)], 2 * HOUR, window);
+        history.record(&[disk("/data", GB, 10 * GB)], 27 * HOUR, window);
+        assert!(!history.mounts.contains_key("/"));
+        assert_eq!(history.mounts["/data"].len(), 1);
+
+        // A resized filesystem starts over
+        history.record(&[disk("/data", 15 * GB, 20 * GB)], 28 * HOUR, window);
+        assert_eq!(history.mounts["/data"].len(), 1);
+        assert_eq!(history.mounts["/data"][0].total_space, 20 * GB);
+    }
+
+    #[test]
+    fn test_update_round_trip() {
+        let path = std::env::temp_dir().join(format!(
+            "system-index-history-{}/{}",
+            std::process::id(),
+            HISTORY_FILE_NAME
+        ));
+        let now = unix_now();
+        let mut history = DiskHistory::default();
+        for hours_ago in (1..=4).rev() {
+            let available = (10 + hours_ago) * GB;
+            history.record(
+                &[disk("/", available, 100 * GB)],
+                now - hours_ago * HOUR,
+                Duration::MAX,
+            );
+        }
+        history.save(&path).unwrap();
+
+        let mut disks = vec![disk("/", 10 * GB, 100 * GB), disk("/new", GB, 2 * GB)];
+        DiskHistory::update(&path, &mut disks, Duration::from_secs(7 * 24 * HOUR)).unwrap();
+        let forecast = disks[0].forecast.as_ref().unwrap();
+        assert_eq!(forecast.samples, 5);
+        // The clock may have ticked since `now`
+        assert!(forecast.seconds_until_full.unwrap().abs_diff(10 * HOUR) <= 1);
+        assert!(disks[1].forecast.is_none());
+        assert_eq!(DiskHistory::load(&path).unwrap().mounts["/new"].len(), 1);
+
+        // Samples saved by another run in the meantime are kept
+        let mut other = DiskHistory::load(&path).unwrap();
+        other.record(&[disk("/cron", GB, 2 * GB)], now, Duration::MAX);
+        other.save(&path).unwrap();
+        let mut disks = vec![disk("/new", GB, 2 * GB)];
+        DiskHistory::update(&path, &mut disks, Duration::from_secs(7 * 24 * HOUR)).unwrap();
+        let merged = DiskHistory::load(&path).unwrap();
+        assert!(merged.mounts.contains_key("/cron"));
+        assert_eq!(merged.mounts["/"].len(), 5);
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+        assert!(DiskHistory::load(&path).unwrap().mounts.is_empty());
+    }
+}
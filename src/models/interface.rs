@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::path::Path;
+
+/// Network interfaces relative to the sysfs root
+const SYSFS_CLASS_NET_DIR: &str = "class/net";
+
+/// An address assigned to a network interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceAddress {
+    pub address: IpAddr,
+    /// Network prefix length, e.g. 24 for a 255.255.255.0 netmask
+    pub prefix_len: u8,
+}
+
+/// Link-layer details of a network interface from `/sys/class/net/<name>`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkInfo {
+    /// Hardware address, e.g. `52:54:00:12:34:56`; `None` for interfaces without one
+    pub mac_address: Option<String>,
+    pub mtu: Option<u32>,
+    /// RFC 2863 state: `up`, `down`, `dormant`, `lowerlayerdown`, `unknown`, …
+    pub operstate: Option<String>,
+    /// Whether a link is detected; `None` while the interface is administratively down
+    pub carrier: Option<bool>,
+    /// `full` or `half`; `None` when the driver does not report it
+    pub duplex: Option<String>,
+    /// Negotiated speed; `None` for virtual interfaces and links that are down
+    pub speed_mbps: Option<u32>,
+}
+
+impl InterfaceAddress {
+    pub fn is_ipv4(&self) -> bool {
+        self.address.is_ipv4()
+    }
+
+    /// Addresses of every interface, keyed by interface name, via `getifaddrs`
+    #[cfg(unix)]
+    pub fn read_all() -> HashMap<String, Vec<Self>> {
+        let mut addresses: HashMap<String, Vec<Self>> = HashMap::new();
+        let mut list: *mut libc::ifaddrs = std::ptr::null_mut();
+        // SAFETY: the list is only walked after a successful call and freed exactly once
+        unsafe {
+            if libc::getifaddrs(&mut list) != 0 {
+                return addresses;
+            }
+            let mut entry = list;
+            while let Some(ifa) = entry.as_ref() {
+                entry = ifa.ifa_next;
+                if ifa.ifa_addr.is_null() || ifa.ifa_netmask.is_null() {
+                    continue;
+                }
+                let Some(address) = Self::from_sockaddrs(ifa.ifa_addr, ifa.ifa_netmask) else {
+                    continue;
+                };
+                let name = std::ffi::CStr::from_ptr(ifa.ifa_name)
+                    .to_string_lossy()
+                    .to_string();
+                addresses.entry(name).or_default().push(address);
+            }
+            libc::freeifaddrs(list);
+        }
+        addresses
+    }
+
+    #[cfg(not(unix))]
+    pub fn read_all() -> HashMap<String, Vec<Self>> {
+        HashMap::new()
+    }
+
+    /// Decode an IPv4 or IPv6 address and netmask; other families yield `None`
+    ///
+    /// # Safety
+    /// Both pointers must point to valid socket addresses of the family in `addr`.
+    #[cfg(unix)]
+    unsafe fn from_sockaddrs(
+        addr: *const libc::sockaddr,
+        netmask: *const libc::sockaddr,
+    ) -> Option<Self> {
+        match i32::from((*addr).sa_family) {
+            libc::AF_INET => {
+                let addr = &*(addr as *const libc::sockaddr_in);
+                let mask = &*(netmask as *const libc::sockaddr_in);
+                Some(Self {
+                    address: IpAddr::from(addr.sin_addr.s_addr.to_ne_bytes()),
+                    prefix_len: prefix_len(&mask.sin_addr.s_addr.to_ne_bytes()),
+                })
+            }
+            libc::AF_INET6 => {
+                let addr = &*(addr as *const libc::sockaddr_in6);
+                let mask = &*(netmask as *const libc::sockaddr_in6);
+                Some(Self {
+                    address: IpAddr::from(addr.sin6_addr.s6_addr),
+                    prefix_len: prefix_len(&mask.sin6_addr.s6_addr),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for InterfaceAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix_len)
+    }
+}
+
+/// Number of leading one bits in a netmask
+fn prefix_len(mask: &[u8]) -> u8 {
+    mask.iter()
+        .take_while(|&&byte| byte == 0xff)
+        .map(|_| 8)
+        .chain(
+            mask.iter()
+                .find(|&&byte| byte != 0xff)
+                .map(|b| b.leading_ones() as u8),
+        )
+        .sum()
+}
+
+impl LinkInfo {
+    /// Read `sysfs_root/class/net/<name>`; missing attributes are left unset
+    pub fn read(sysfs_root: &Path, name: &str) -> Self {
+        let dir = sysfs_root.join(SYSFS_CLASS_NET_DIR).join(name);
+        // Reading speed or duplex of a link that is down fails with EINVAL
+        let read = |file: &str| {
+            std::fs::read_to_string(dir.join(file))
+                .ok()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+
+        Self {
+            mac_address: read("address").filter(|mac| mac != "00:00:00:00:00:00"),
+            mtu: read("mtu").and_then(|mtu| mtu.parse().ok()),
+            operstate: read("operstate"),
+            carrier: read("carrier").map(|carrier| carrier == "1"),
+            duplex: read("duplex").filter(|duplex| duplex != "unknown"),
+            // Virtual interfaces report -1
+            speed_mbps: read("speed")
+                .and_then(|speed| speed.parse::<i64>().ok())
+                .and_then(|speed| u32::try_from(speed).ok())
+                .filter(|&speed| speed > 0),
+        }
+    }
+
+    /// e.g. `up, 1000 Mb/s full duplex, MTU 1500` or `down, no carrier, MTU 1500`
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(state) = &self.operstate {
+            parts.push(state.clone());
+        }
+        if self.carrier == Some(false) {
+            parts.push("no carrier".to_string());
+        }
+        match (self.speed_mbps, &self.duplex) {
+            (Some(speed), Some(duplex)) => parts.push(format!("{} Mb/s {} duplex", speed, duplex)),
+            (Some(speed), None) => parts.push(format!("{} Mb/s", speed)),
+            (None, Some(duplex)) => parts.push(format!("{} duplex", duplex)),
+            (None, None) => {}
+        }
+        if let Some(mtu) = self.mtu {
+            parts.push(format!("MTU {}", mtu));
+        }
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixture_path;
+
+    fn fixture(name: &str) -> LinkInfo {
+        LinkInfo::read(&fixture_path("sysfs"), name)
+    }
+
+    #[test]
+    fn test_read_physical_link() {
+        let eth0 = fixture("eth0");
+        assert_eq!(eth0.mac_address.as_deref(), Some("52:54:00:12:34:56"));
+        assert_eq!(eth0.mtu, Some(1500));
+        assert_eq!(eth0.carrier, Some(true));
+        assert_eq!(eth0.speed_mbps, Some(1000));
+        assert_eq!(eth0.summary(), "up, 1000 Mb/s full duplex, MTU 1500");
+    }
+
+    #[test]
+    fn test_read_virtual_and_down_links() {
+        let lo = fixture("lo");
+        assert!(lo.mac_address.is_none());
+        assert!(lo.speed_mbps.is_none());
+        assert_eq!(lo.summary(), "unknown, MTU 65536");
+
+        // speed and duplex are unreadable while the link is down
+        let wlan0 = fixture("wlan0");
+        assert_eq!(wlan0.carrier, Some(false));
+        assert!(wlan0.duplex.is_none());
+        assert_eq!(wlan0.summary(), "down, no carrier, MTU 1500");
+
+        assert_eq!(fixture("missing0"), LinkInfo::default());
+    }
+
+    #[test]
+    fn test_prefix_len() {
+        assert_eq!(prefix_len(&[255, 255, 255, 0]), 24);
+        assert_eq!(prefix_len(&[255, 255, 240, 0]), 20);
+        assert_eq!(prefix_len(&[0, 0, 0, 0]), 0);
+        assert_eq!(prefix_len(&[0xff; 16]), 128);
+        let mut v6 = [0u8; 16];
+        v6[..8].fill(0xff);
+        assert_eq!(prefix_len(&v6), 64);
+    }
+
+    #[test]
+    fn test_display() {
+        let address = InterfaceAddress {
+            address: "fe80::1".parse().unwrap(),
+            prefix_len: 64,
+        };
+        assert_eq!(address.to_string(), "fe80::1/64");
+        assert!(!address.is_ipv4());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_all_has_loopback() {
+        let addresses = InterfaceAddress::read_all();
+        // Sandboxes without a loopback address are fine; any one found must be valid
+        if let Some(lo) = addresses.get("lo") {
+            assert!(lo.iter().all(|a| a.address.is_loopback()));
+        }
+    }
+}
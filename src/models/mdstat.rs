@@ -0,0 +1,360 @@
+use super::SystemInfo;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Status of the md software RAID driver
+pub const PROC_MDSTAT: &str = "/proc/mdstat";
+
+/// An md software RAID array from `/proc/mdstat`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MdArray {
+    /// e.g. `md0`
+    pub name: String,
+    /// `false` for arrays that are assembled but not started
+    pub active: bool,
+    pub read_only: bool,
+    /// e.g. `raid1`; unknown for inactive arrays
+    pub level: Option<String>,
+    pub members: Vec<MdMember>,
+    /// Devices the array is made of when complete
+    pub raid_devices: Option<u32>,
+    /// Devices currently in sync
+    pub active_devices: Option<u32>,
+    /// Per-slot status such as `UU_`, where `_` is a missing device
+    pub status_map: Option<String>,
+    /// Resync, recovery, reshape or check in progress
+    pub sync: Option<MdSyncProgress>,
+}
+
+/// A component device of an md array
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MdMember {
+    pub device: String,
+    /// Slot number in the array
+    pub role: u32,
+    /// Marked `(F)`
+    pub faulty: bool,
+    /// Marked `(S)`
+    pub spare: bool,
+}
+
+/// Progress of a background operation on an md array
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MdSyncProgress {
+    /// `resync`, `recovery`, `reshape`, `check` or `repair`
+    pub action: String,
+    /// `None` while the operation is delayed or pending
+    pub percent: Option<f64>,
+    /// Estimated time to completion in seconds
+    pub eta_secs: Option<u64>,
+    /// Current speed in KiB/s
+    pub speed_kib: Option<u64>,
+}
+
+impl MdArray {
+    /// Read every array from a `/proc/mdstat` file; empty when md is not loaded
+    pub fn read(path: &Path) -> Vec<Self> {
+        std::fs::read_to_string(path)
+            .map(|contents| Self::parse(&contents))
+            .unwrap_or_default()
+    }
+
+    /// Parse `/proc/mdstat` contents, keeping the kernel's order
+    pub fn parse(contents: &str) -> Vec<Self> {
+        let mut arrays: Vec<Self> = Vec::new();
+        for line in contents.lines() {
+            if let Some((name, description)) = line.split_once(" : ") {
+                if name.starts_with("md") {
+                    arrays.extend(Self::parse_header(name.trim(), description));
+                }
+                continue;
+            }
+            // Detail lines are indented below their array's header
+            let Some(array) = arrays.last_mut() else {
+                continue;
+            };
+            if !line.starts_with(char::is_whitespace) {
+                continue;
+            }
+            array.parse_detail(line.trim());
+        }
+        arrays
+    }
+
+    /// Parse e.g. `active (auto-read-only) raid1 sdb1[1] sda1[0](F)`
+    fn parse_header(name: &str, description: &str) -> Option<Self> {
+        let mut fields = description.split_whitespace().peekable();
+        let active = match fields.next()? {
+            "active" => true,
+            "inactive" => false,
+            _ => return None,
+        };
+        let read_only = fields
+            .next_if(|f| matches!(*f, "(read-only)" | "(auto-read-only)"))
+            .is_some();
+        let level = fields.next_if(|f| !f.contains('[')).map(str::to_string);
+        let mut members: Vec<MdMember> = fields.filter_map(MdMember::parse).collect();
+        members.sort_by_key(|member| member.role);
+
+        Some(Self {
+            name: name.to_string(),
+            active,
+            read_only,
+            level,
+            members,
+            raid_devices: None,
+            active_devices: None,
+            status_map: None,
+            sync: None,
+        })
+    }
+
+    /// Parse a detail line: block counts with `[n/m] [UU_]`, or sync progress
+    fn parse_detail(&mut self, line: &str) {
+        if let Some(progress) = MdSyncProgress::parse(line) {
+            self.sync = Some(progress);
+            return;
+        }
+        if !line.contains(" blocks") {
+            return;
+        }
+        for field in line.split_whitespace() {
+            let Some(inner) = field.strip_prefix('[').and_then(|f| f.strip_suffix(']')) else {
+                continue;
+            };
+            if let Some((total, up)) = inner.split_once('/') {
+                self.raid_devices = total.parse().ok();
+                self.active_devices = up.parse().ok();
+            } else if !inner.is_empty() && inner.chars().all(|c| c == 'U' || c == '_') {
+                self.status_map = Some(inner.to_string());
+            }
+        }
+    }
+
+    /// Fewer devices in sync than the array needs, or a failed member
+    pub fn is_degraded(&self) -> bool {
+        let missing = matches!(
+            (self.raid_devices, self.active_devices),
+            (Some(total), Some(up)) if up < total
+        );
+        missing || !self.failed_members().is_empty()
+    }
+
+    pub fn failed_members(&self) -> Vec<&MdMember> {
+        self.members.iter().filter(|m| m.faulty).collect()
+    }
+
+    pub fn spare_members(&self) -> Vec<&MdMember> {
+        self.members.iter().filter(|m| m.spare).collect()
+    }
+
+    /// `clean`, `DEGRADED`, `inactive` plus any read-only flag
+    pub fn state_label(&self) -> String {
+        let state = if !self.active {
+            "inactive"
+        } else if self.is_degraded() {
+            "DEGRADED"
+        } else {
+            "clean"
+        };
+        if self.read_only {
+            format!("{}, read-only", state)
+        } else {
+            state.to_string()
+        }
+    }
+
+    /// Summary lines, e.g. `md1 raid5 [4/3] [U_UU] DEGRADED` followed by members and progress
+    pub fn summary_lines(&self) -> Vec<String> {
+        let mut header = format!(
+            "{} {}",
+            self.name,
+            self.level.as_deref().unwrap_or("unknown")
+        );
+        if let (Some(total), Some(up)) = (self.raid_devices, self.active_devices) {
+            header.push_str(&format!(" [{}/{}]", total, up));
+        }
+        if let Some(map) = &self.status_map {
+            header.push_str(&format!(" [{}]", map));
+        }
+        header.push_str(&format!(" {}", self.state_label()));
+
+        let members: Vec<String> = self
+            .members
+            .iter()
+            .map(|member| {
+                let flag = if member.faulty {
+                    " (failed)"
+                } else if member.spare {
+                    " (spare)"
+                } else {
+                    ""
+                };
+                format!("{}{}", member.device, flag)
+            })
+            .collect();
+        let mut lines = vec![header, format!("  members: {}", members.join(", "))];
+        if let Some(sync) = &self.sync {
+            lines.push(format!("  {}", sync.summary()));
+        }
+        lines
+    }
+}
+
+impl MdMember {
+    /// Parse e.g. `sdd1[1](F)`
+    fn parse(field: &str) -> Option<Self> {
+        let (device, rest) = field.split_once('[')?;
+        let (role, flags) = rest.split_once(']')?;
+        Some(Self {
+            device: device.to_string(),
+            role: role.parse().ok()?,
+            faulty: flags.contains("(F)"),
+            spare: flags.contains("(S)"),
+        })
+    }
+}
+
+impl MdSyncProgress {
+    /// Parse a progress line such as
+    /// `[=>....]  recovery =  8.5% (166275584/1953423360) finish=151.2min speed=196888K/sec`
+    /// or a queued operation such as `resync=DELAYED`
+    fn parse(line: &str) -> Option<Self> {
+        const ACTIONS: [&str; 5] = ["resync", "recovery", "reshape", "check", "repair"];
+
+        let line = match line.strip_prefix('[') {
+            Some(bar) => bar.split_once(']')?.1,
+            None => line,
+        };
+        let (action, rest) = line.trim().split_once('=')?;
+        let action = action.trim();
+        if !ACTIONS.contains(&action) {
+            return None;
+        }
+        let mut fields = rest.split_whitespace();
+        let percent = fields
+            .next()
+            .and_then(|f| f.strip_suffix('%'))
+            .and_then(|f| f.parse().ok());
+
+        let mut progress = Self {
+            action: action.to_string(),
+            percent,
+            eta_secs: None,
+            speed_kib: None,
+        };
+        for field in fields {
+            if let Some(minutes) = field
+                .strip_prefix("finish=")
+                .and_then(|f| f.strip_suffix("min"))
+            {
+                progress.eta_secs = minutes
+                    .parse::<f64>()
+                    .ok()
+                    .map(|m| (m * 60.0).round() as u64);
+            } else if let Some(speed) = field
+                .strip_prefix("speed=")
+                .and_then(|f| f.strip_suffix("K/sec"))
+            {
+                progress.speed_kib = speed.parse().ok();
+            }
+        }
+        Some(progress)
+    }
+
+    /// e.g. `recovery 8.5%, 192.27 MB/s, ETA 2h 31m 12s` or `resync pending`
+    pub fn summary(&self) -> String {
+        let Some(percent) = self.percent else {
+            return format!("{} pending", self.action);
+        };
+        let mut summary = format!("{} {:.1}%", self.action, percent);
+        if let Some(speed) = self.speed_kib {
+            summary.push_str(&format!(", {}/s", SystemInfo::format_bytes(speed * 1024)));
+        }
+        if let Some(eta) = self.eta_secs {
+            summary.push_str(&format!(", ETA {}", SystemInfo::format_uptime(eta)));
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixture_path;
+
+    #[test]
+    fn test_parse_healthy_mirror() {
+        let arrays = MdArray::read(&fixture_path("proc/mdstat"));
+        assert_eq!(arrays.len(), 1);
+
+        let md0 = &arrays[0];
+        assert_eq!(md0.name, "md0");
+        assert_eq!(md0.level.as_deref(), Some("raid1"));
+        let members: Vec<&str> = md0.members.iter().map(|m| m.device.as_str()).collect();
+        assert_eq!(members, ["sda1", "sdb1"]);
+        assert_eq!((md0.raid_devices, md0.active_devices), (Some(2), Some(2)));
+        assert_eq!(md0.status_map.as_deref(), Some("UU"));
+        assert!(md0.sync.is_none());
+        assert!(!md0.is_degraded());
+        assert_eq!(md0.state_label(), "clean");
+    }
+
+    #[test]
+    fn test_parse_degraded_recovery() {
+        let arrays = MdArray::read(&fixture_path("mdstat/degraded_recovery"));
+        let names: Vec<&str> = arrays.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["md1", "md2", "md0"]);
+
+        let md1 = &arrays[0];
+        assert!(md1.is_degraded());
+        assert_eq!(md1.failed_members()[0].device, "sdd1");
+        let sync = md1.sync.as_ref().unwrap();
+        assert_eq!(sync.action, "recovery");
+        assert_eq!(sync.percent, Some(8.5));
+        assert_eq!(sync.eta_secs, Some(9072));
+        assert_eq!(sync.speed_kib, Some(196_888));
+        assert_eq!(
+            md1.summary_lines(),
+            [
+                "md1 raid5 [4/3] [U_UU] DEGRADED",
+                "  members: sdc1, sdd1 (failed), sde1, sdf1",
+                "  recovery 8.5%, 192.27 MB/s, ETA 2h 31m 12s",
+            ]
+        );
+
+        // A missing mirror half with only a spare left is degraded too
+        let md2 = &arrays[1];
+        assert!(md2.is_degraded());
+        assert!(md2.failed_members().is_empty());
+        assert_eq!(md2.spare_members()[0].device, "sdh1");
+
+        let md0 = &arrays[2];
+        assert!(!md0.is_degraded());
+        assert_eq!(md0.sync.as_ref().unwrap().summary(), "resync pending");
+    }
+
+    #[test]
+    fn test_parse_resync_and_inactive() {
+        let arrays = MdArray::read(&fixture_path("mdstat/resync_inactive"));
+        let md10 = &arrays[0];
+        assert!(md10.read_only);
+        assert_eq!(md10.level.as_deref(), Some("raid10"));
+        assert_eq!(md10.members.len(), 4);
+        assert_eq!(md10.sync.as_ref().unwrap().percent, Some(52.3));
+        assert_eq!(md10.state_label(), "clean, read-only");
+
+        let md127 = &arrays[1];
+        assert!(!md127.active);
+        assert!(md127.level.is_none());
+        assert_eq!(md127.spare_members().len(), 2);
+        assert_eq!(md127.state_label(), "inactive");
+        assert_eq!(md127.summary_lines()[0], "md127 unknown inactive");
+    }
+
+    #[test]
+    fn test_missing_mdstat() {
+        assert!(MdArray::read(Path::new("/nonexistent/mdstat")).is_empty());
+        assert!(MdArray::parse("Personalities : \nunused devices: <none>\n").is_empty());
+    }
+}
@@ -0,0 +1,443 @@
+use super::swap::{SwapDevice, ZramDevice, ZswapStatus};
+use super::SystemInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Transparent Huge Pages settings relative to the sysfs root
+const SYSFS_THP_DIR: &str = "kernel/mm/transparent_hugepage";
+
+/// Memory details beyond the totals reported by `sysinfo`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryDetails {
+    /// Breakdown from `/proc/meminfo` (Linux only)
+    pub meminfo: Option<MemInfo>,
+    /// Active swap areas from `/proc/swaps`
+    #[serde(default)]
+    pub swap_devices: Vec<SwapDevice>,
+    /// Compressed RAM block devices
+    #[serde(default)]
+    pub zram: Vec<ZramDevice>,
+    /// Compressed swap cache, when the zswap module is present
+    #[serde(default)]
+    pub zswap: Option<ZswapStatus>,
+    #[serde(default)]
+    pub huge_pages: Option<HugePages>,
+}
+
+/// How RAM is divided, from `/proc/meminfo`; all values in bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemInfo {
+    pub total: u64,
+    pub free: u64,
+    /// Estimate of memory available for new workloads without swapping
+    pub available: u64,
+    pub buffers: u64,
+    /// Page cache, excluding swap cache
+    pub cached: u64,
+    /// tmpfs and shared memory
+    pub shared: u64,
+    pub dirty: u64,
+    pub writeback: u64,
+    pub slab_reclaimable: u64,
+    pub slab_unreclaimable: u64,
+    /// Memory allocated by processes, including not yet touched pages
+    pub committed: u64,
+    /// Allocation limit under strict overcommit accounting
+    pub commit_limit: u64,
+}
+
+/// Static huge page pool and Transparent Huge Pages status
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HugePages {
+    /// Pages in the static pool
+    pub total: u64,
+    pub free: u64,
+    /// Pages promised to mappings but not yet faulted in
+    pub reserved: u64,
+    /// Pages allocated beyond `total` through overcommit
+    pub surplus: u64,
+    pub page_size_bytes: u64,
+    /// Memory consumed by huge pages of all sizes
+    pub hugetlb_bytes: u64,
+    /// Anonymous memory backed by transparent huge pages
+    pub anon_huge_bytes: u64,
+    /// Selected THP mode: `always`, `madvise` or `never`
+    pub thp_enabled: Option<String>,
+    /// Selected THP defrag mode
+    pub thp_defrag: Option<String>,
+}
+
+/// One part of the RAM stacked bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemorySegment {
+    pub label: &'static str,
+    pub glyph: char,
+    pub bytes: u64,
+}
+
+impl MemoryDetails {
+    /// Read memory details below `proc_root` (normally `/proc`) and
+    /// `sysfs_root` (normally `/sys`)
+    pub fn read(proc_root: &Path, sysfs_root: &Path) -> Self {
+        let values = std::fs::read_to_string(proc_root.join("meminfo"))
+            .map(|contents| parse_meminfo(&contents))
+            .unwrap_or_default();
+
+        Self {
+            meminfo: MemInfo::from_values(&values),
+            swap_devices: SwapDevice::read(&proc_root.join("swaps")),
+            zram: ZramDevice::read_all(sysfs_root),
+            zswap: ZswapStatus::read(sysfs_root, &values),
+            huge_pages: HugePages::from_values(&values, sysfs_root),
+        }
+    }
+
+    /// Swap device, zram, zswap and huge page sections, each headed by a
+    /// `═══ TITLE ═══` line and preceded by a blank line; empty sections are skipped
+    pub fn section_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut section = |title: &str, body: Vec<String>| {
+            if !body.is_empty() {
+                lines.push(String::new());
+                lines.push(format!("═══ {} ═══", title));
+                lines.extend(body);
+            }
+        };
+
+        section(
+            "SWAP DEVICES",
+            self.swap_devices.iter().map(SwapDevice::summary).collect(),
+        );
+        section("ZRAM", self.zram.iter().map(ZramDevice::summary).collect());
+        section(
+            "ZSWAP",
+            self.zswap.iter().map(ZswapStatus::summary).collect(),
+        );
+        section(
+            "HUGE PAGES",
+            self.huge_pages
+                .as_ref()
+                .map(HugePages::summary_lines)
+                .unwrap_or_default(),
+        );
+        lines
+    }
+}
+
+impl MemInfo {
+    /// Read and parse a `/proc/meminfo` file
+    pub fn read(path: &Path) -> Option<Self> {
+        Self::parse(&std::fs::read_to_string(path).ok()?)
+    }
+
+    /// Parse `/proc/meminfo` contents; requires at least `MemTotal` and `MemFree`
+    pub fn parse(contents: &str) -> Option<Self> {
+        Self::from_values(&parse_meminfo(contents))
+    }
+
+    /// Build from fields returned by [`parse_meminfo`]
+    pub fn from_values(values: &HashMap<String, u64>) -> Option<Self> {
+        let field = |key: &str| values.get(key).copied().unwrap_or(0);
+
+        let total = *values.get("MemTotal")?;
+        let free = *values.get("MemFree")?;
+        let buffers = field("Buffers");
+        let cached = field("Cached");
+        // MemAvailable appeared in Linux 3.14; approximate it on older kernels
+        let available = values
+            .get("MemAvailable")
+            .copied()
+            .unwrap_or(free + buffers + cached);
+
+        Some(Self {
+            total,
+            free,
+            available,
+            buffers,
+            cached,
+            shared: field("Shmem"),
+            dirty: field("Dirty"),
+            writeback: field("Writeback"),
+            slab_reclaimable: field("SReclaimable"),
+            slab_unreclaimable: field("SUnreclaim"),
+            committed: field("Committed_AS"),
+            commit_limit: field("CommitLimit"),
+        })
+    }
+
+    /// Memory in use by processes and the kernel, excluding reclaimable caches
+    ///
+    /// Matches the "used" column of `free`.
+    pub fn used(&self) -> u64 {
+        self.total
+            .saturating_sub(self.free)
+            .saturating_sub(self.buffers)
+            .saturating_sub(self.cache())
+    }
+
+    /// Page cache plus reclaimable slab, matching the cache part of `free`'s "buff/cache"
+    pub fn cache(&self) -> u64 {
+        self.cached + self.slab_reclaimable
+    }
+
+    /// RAM divided into used, buffers, cache plus reclaimable slab and free;
+    /// the parts sum to `total`
+    pub fn segments(&self) -> [MemorySegment; 4] {
+        [
+            MemorySegment {
+                label: "Used",
+                glyph: '█',
+                bytes: self.used(),
+            },
+            MemorySegment {
+                label: "Buffers",
+                glyph: '▓',
+                bytes: self.buffers,
+            },
+            MemorySegment {
+                label: "Cache+Slab",
+                glyph: '▒',
+                bytes: self.cache(),
+            },
+            MemorySegment {
+                label: "Free",
+                glyph: '░',
+                bytes: self.free,
+            },
+        ]
+    }
+
+    /// Render [`MemInfo::segments`] as a bar exactly `width` characters wide
+    pub fn stacked_bar(&self, width: usize) -> String {
+        let segments = self.segments();
+        let total: u64 = segments.iter().map(|s| s.bytes).sum();
+        if total == 0 {
+            return " ".repeat(width);
+        }
+
+        // Place each boundary by cumulative share so rounding never drifts
+        let mut bar = String::new();
+        let mut cumulative = 0u64;
+        let mut drawn = 0usize;
+        for segment in segments {
+            cumulative += segment.bytes;
+            let end = (cumulative as f64 / total as f64 * width as f64).round() as usize;
+            bar.extend(std::iter::repeat_n(segment.glyph, end - drawn));
+            drawn = end;
+        }
+        bar
+    }
+
+    /// Legend for the stacked bar, e.g. `█ Used 1.00 GB  ▓ Buffers …`
+    pub fn legend(&self) -> String {
+        self.segments()
+            .iter()
+            .map(|s| {
+                format!(
+                    "{} {} {}",
+                    s.glyph,
+                    s.label,
+                    SystemInfo::format_bytes(s.bytes)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+}
+
+impl HugePages {
+    /// Build from `/proc/meminfo` fields, reading THP modes below `sysfs_root`
+    ///
+    /// Returns `None` when the kernel has no huge page support.
+    pub fn from_values(values: &HashMap<String, u64>, sysfs_root: &Path) -> Option<Self> {
+        let field = |key: &str| values.get(key).copied().unwrap_or(0);
+        let thp_dir = sysfs_root.join(SYSFS_THP_DIR);
+        let thp_mode = |file: &str| {
+            std::fs::read_to_string(thp_dir.join(file))
+                .ok()
+                .and_then(|contents| selected_option(&contents))
+        };
+
+        Some(Self {
+            total: *values.get("HugePages_Total")?,
+            free: field("HugePages_Free"),
+            reserved: field("HugePages_Rsvd"),
+            surplus: field("HugePages_Surp"),
+            page_size_bytes: field("Hugepagesize"),
+            hugetlb_bytes: field("Hugetlb"),
+            anon_huge_bytes: field("AnonHugePages"),
+            thp_enabled: thp_mode("enabled"),
+            thp_defrag: thp_mode("defrag"),
+        })
+    }
+
+    /// Pages of the static pool in use
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.free)
+    }
+
+    /// Summary lines for the static pool and THP
+    pub fn summary_lines(&self) -> Vec<String> {
+        vec![
+            format!(
+                "Static pool: {} of {} pages of {} in use ({} reserved, {} surplus)",
+                self.used(),
+                self.total,
+                SystemInfo::format_bytes(self.page_size_bytes),
+                self.reserved,
+                self.surplus
+            ),
+            format!(
+                "Hugetlb:     {}",
+                SystemInfo::format_bytes(self.hugetlb_bytes)
+            ),
+            format!(
+                "THP:         {} (defrag: {}), {} anonymous",
+                self.thp_enabled.as_deref().unwrap_or("unavailable"),
+                self.thp_defrag.as_deref().unwrap_or("unknown"),
+                SystemInfo::format_bytes(self.anon_huge_bytes)
+            ),
+        ]
+    }
+}
+
+/// The bracketed choice of a sysfs option list such as `always [madvise] never`
+pub fn selected_option(contents: &str) -> Option<String> {
+    contents
+        .split_whitespace()
+        .find_map(|option| option.strip_prefix('[')?.strip_suffix(']'))
+        .map(str::to_string)
+}
+
+/// Parse `/proc/meminfo` into a map of field name to value
+///
+/// Values with a `kB` unit are converted to bytes; unitless counts are kept as is.
+pub fn parse_meminfo(contents: &str) -> HashMap<String, u64> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let mut parts = rest.split_whitespace();
+            let value: u64 = parts.next()?.parse().ok()?;
+            let value = match parts.next() {
+                Some("kB") => value * 1024,
+                _ => value,
+            };
+            Some((key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixture_path;
+
+    const MEMINFO: &str = "MemTotal:       16000000 kB
+MemFree:         2000000 kB
+MemAvailable:    9000000 kB
+Buffers:          500000 kB
+Cached:          6000000 kB
+SwapCached:            0 kB
+Shmem:            300000 kB
+Dirty:              1200 kB
+Writeback:             0 kB
+SReclaimable:     500000 kB
+SUnreclaim:       200000 kB
+CommitLimit:    12000000 kB
+Committed_AS:   10000000 kB
+HugePages_Total:       0
+";
+
+    const KIB: u64 = 1024;
+
+    #[test]
+    fn test_parse_meminfo() {
+        let values = parse_meminfo(MEMINFO);
+        assert_eq!(values["MemTotal"], 16_000_000 * KIB);
+        assert_eq!(values["HugePages_Total"], 0);
+
+        let info = MemInfo::parse(MEMINFO).unwrap();
+        assert_eq!(info.available, 9_000_000 * KIB);
+        assert_eq!(info.shared, 300_000 * KIB);
+        assert_eq!(info.dirty, 1_200 * KIB);
+        assert_eq!(info.slab_unreclaimable, 200_000 * KIB);
+        assert_eq!(info.committed, 10_000_000 * KIB);
+        assert_eq!(info.commit_limit, 12_000_000 * KIB);
+    }
+
+    #[test]
+    fn test_used_excludes_caches() {
+        let info = MemInfo::parse(MEMINFO).unwrap();
+        assert_eq!(info.cache(), 6_500_000 * KIB);
+        assert_eq!(info.used(), 7_000_000 * KIB);
+
+        let sum: u64 = info.segments().iter().map(|s| s.bytes).sum();
+        assert_eq!(sum, info.total);
+    }
+
+    #[test]
+    fn test_available_fallback_for_old_kernels() {
+        let info =
+            MemInfo::parse("MemTotal: 1000 kB\nMemFree: 100 kB\nBuffers: 50 kB\nCached: 200 kB\n")
+                .unwrap();
+        assert_eq!(info.available, 350 * KIB);
+        assert!(MemInfo::parse("MemTotal: 1000 kB\n").is_none());
+    }
+
+    #[test]
+    fn test_selected_option() {
+        assert_eq!(
+            selected_option("always [madvise] never\n").as_deref(),
+            Some("madvise")
+        );
+        assert_eq!(selected_option("lzo lz4 [zstd]").as_deref(), Some("zstd"));
+        assert_eq!(selected_option("zstd"), None);
+    }
+
+    #[test]
+    fn test_read_fixture_memory_details() {
+        let details = MemoryDetails::read(&fixture_path("proc"), &fixture_path("sysfs"));
+
+        let meminfo = details.meminfo.unwrap();
+        assert_eq!(meminfo.total, 16_000_000 * KIB);
+
+        let huge_pages = details.huge_pages.as_ref().unwrap();
+        assert_eq!(huge_pages.total, 512);
+        assert_eq!(huge_pages.used(), 412);
+        assert_eq!(huge_pages.page_size_bytes, 2048 * KIB);
+        assert_eq!(huge_pages.thp_enabled.as_deref(), Some("madvise"));
+        assert_eq!(huge_pages.thp_defrag.as_deref(), Some("defer"));
+        assert_eq!(
+            huge_pages.summary_lines()[0],
+            "Static pool: 412 of 512 pages of 2.00 MB in use (20 reserved, 0 surplus)"
+        );
+
+        assert_eq!(details.swap_devices.len(), 2);
+        assert_eq!(details.zram.len(), 1);
+        assert!(details.zswap.as_ref().unwrap().enabled);
+
+        let lines = details.section_lines();
+        assert!(lines.contains(&"═══ ZRAM ═══".to_string()));
+        assert_eq!(
+            lines.iter().filter(|line| line.starts_with("═══")).count(),
+            4
+        );
+        assert!(MemoryDetails::default().section_lines().is_empty());
+    }
+
+    #[test]
+    fn test_stacked_bar() {
+        let info = MemInfo::parse(MEMINFO).unwrap();
+        let bar = info.stacked_bar(32);
+        assert_eq!(bar.chars().count(), 32);
+        // 7/16 used, 0.5/16 buffers, 6.5/16 cache, 2/16 free
+        assert_eq!(bar.chars().filter(|c| *c == '█').count(), 14);
+        assert_eq!(bar.chars().filter(|c| *c == '▓').count(), 1);
+        assert_eq!(bar.chars().filter(|c| *c == '▒').count(), 13);
+        assert_eq!(bar.chars().filter(|c| *c == '░').count(), 4);
+
+        assert_eq!(MemInfo::default().stacked_bar(4), "    ");
+    }
+}
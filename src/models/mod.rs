@@ -1,27 +1,117 @@
+use crate::probes::{self, PublicIpResolver};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
 use sysinfo::{Disks, Networks, System};
 
+pub mod block;
+pub mod cgroup;
+mod collect;
+pub mod cpu;
+pub mod diskstats;
+pub mod features;
+pub mod filesystem;
+pub mod forecast;
+pub mod interface;
+pub mod mdstat;
+pub mod memory;
+pub mod mountinfo;
+pub mod netdev;
+pub mod pressure;
+pub mod swap;
+pub mod topology;
+pub mod virt;
+
+pub use block::{BlockDevice, BlockDeviceKind};
+pub use cgroup::{CgroupLimits, CgroupVersion};
+pub use collect::{CollectOptions, Section, DISK_SAMPLE_INTERVAL};
+pub use cpu::{CoreInfo, CpuDetails, CpuTimeSample, CpuTimes, LoadAverage};
+pub use diskstats::{DiskIoStats, DiskStatsSample};
+pub use features::{CpuFeatures, CpuVulnerability, VulnerabilityState};
+pub use filesystem::{DiskFilter, InodeUsage};
+pub use forecast::{DiskForecast, DiskHistory, DiskSample};
+pub use interface::{InterfaceAddress, LinkInfo};
+pub use mdstat::{MdArray, MdMember, MdSyncProgress};
+pub use memory::{HugePages, MemInfo, MemoryDetails, MemorySegment};
+pub use mountinfo::MountEntry;
+pub use netdev::{InterfaceStats, NetDevSample, NetworkRates};
+pub use pressure::{Pressure, PressureStall, ResourcePressure};
+pub use swap::{SwapDevice, ZramDevice, ZswapStatus};
+pub use topology::{CpuCache, CpuPackage, CpuTopology, NumaNode, PhysicalCore};
+pub use virt::{ContainerRuntime, DetectionHints, Environment, Hypervisor};
+
 /// Bytes per kilobyte/megabyte/etc unit
 const BYTES_PER_UNIT: f64 = 1024.0;
 
+/// Mount point of the kernel's sysfs
+pub const SYSFS_ROOT: &str = "/sys";
+
+/// Mount point of procfs
+pub const PROC_ROOT: &str = "/proc";
+
+/// Whole block devices relative to the sysfs root
+const SYSFS_BLOCK_DIR: &str = "block";
+
+/// Unit of the sector counts in `/sys/block/*/size` and `/proc/diskstats`,
+/// regardless of the device's actual sector size
+const SECTOR_SIZE: u64 = 512;
+
+/// Increase of a cumulative kernel counter between two samples
+///
+/// Counters restart from zero when a device or interface is re-created, so
+/// the increase never goes negative.
+fn counter_delta(before: u64, after: u64) -> u64 {
+    after.saturating_sub(before)
+}
+
+/// Path below the `tests/fixtures` directory, e.g. `fixture_path("sysfs")`
+#[cfg(test)]
+pub(crate) fn fixture_path(relative: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(relative)
+}
+
 /// System information model
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SystemInfo {
     pub os_name: String,
     pub os_version: String,
     pub kernel_version: String,
     pub hostname: String,
+    /// Hypervisor and container runtime detected at collection time
+    #[serde(default)]
+    pub environment: Environment,
     pub cpu_count: usize,
     pub cpu_brand: String,
+    #[serde(default)]
+    pub cpu: CpuDetails,
     pub total_memory: u64,
     pub used_memory: u64,
     pub total_swap: u64,
     pub used_swap: u64,
+    #[serde(default)]
+    pub memory: MemoryDetails,
     pub disks: Vec<DiskInfo>,
+    /// Block device I/O rates over the sample interval (Linux only)
+    #[serde(default)]
+    pub disk_io: Vec<DiskIoStats>,
+    /// Block device hierarchy: disks, partitions, device-mapper and md (Linux only)
+    #[serde(default)]
+    pub block_devices: Vec<BlockDevice>,
+    /// md software RAID arrays from `/proc/mdstat` (Linux only)
+    #[serde(default)]
+    pub raid_arrays: Vec<MdArray>,
     pub networks: Vec<NetworkInfo>,
     pub network_details: NetworkDetails,
     pub processes_count: usize,
     pub uptime: u64,
+    #[serde(default)]
+    pub pressure: Pressure,
+    /// Limits of the cgroup this process runs in (Linux only)
+    #[serde(default)]
+    pub cgroup: Option<CgroupLimits>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,97 +121,340 @@ pub struct DiskInfo {
     pub total_space: u64,
     pub available_space: u64,
     pub file_system: String,
+    /// `None` when the filesystem has no fixed inode table
+    #[serde(default)]
+    pub inodes: Option<InodeUsage>,
+    /// `major:minor` of the mounted device, from `/proc/self/mountinfo`
+    #[serde(default)]
+    pub dev: Option<String>,
+    /// Mount source, e.g. `/dev/mapper/vg0-root`
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Per-mount options, e.g. `rw`, `noatime`
+    #[serde(default)]
+    pub mount_options: Vec<String>,
+    /// Fill forecast from recorded history; `None` until enough is recorded
+    #[serde(default)]
+    pub forecast: Option<DiskForecast>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkInfo {
     pub interface_name: String,
+    /// Bytes received since the interface came up
     pub received_bytes: u64,
+    /// Bytes transmitted since the interface came up
     pub transmitted_bytes: u64,
+    /// First IPv4 address, or the first address of any family, without prefix
     pub ip_address: Option<String>,
+    /// IPv4 and IPv6 addresses with prefix lengths
+    #[serde(default)]
+    pub addresses: Vec<InterfaceAddress>,
+    #[serde(default)]
+    pub link: LinkInfo,
+    /// Throughput over the sampling window; `None` when not sampled
+    #[serde(default)]
+    pub rates: Option<NetworkRates>,
+    /// Packet, error and drop counters; `None` without `/proc/net/dev`
+    #[serde(default)]
+    pub stats: Option<InterfaceStats>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct NetworkDetails {
     pub local_ip: Option<String>,
     pub public_ip: Option<String>,
     pub bandwidth_mbps: Option<f64>,
+    #[serde(default)]
+    pub public_ip_status: ProbeStatus,
+    #[serde(default)]
+    pub bandwidth_status: ProbeStatus,
+}
+
+/// Outcome of an external network probe
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeStatus {
+    /// The probe was not run because external probes are disabled
+    #[default]
+    Skipped,
+    /// The probe ran but no service answered
+    Unavailable,
+    /// The probe produced a value
+    Ok,
+}
+
+impl ProbeStatus {
+    fn of<T>(value: &Option<T>) -> Self {
+        if value.is_some() {
+            ProbeStatus::Ok
+        } else {
+            ProbeStatus::Unavailable
+        }
+    }
+}
+
+impl NetworkDetails {
+    /// Public IP for display, or why it is missing
+    pub fn public_ip_display(&self) -> String {
+        match (&self.public_ip, self.public_ip_status) {
+            (Some(ip), _) => ip.clone(),
+            (None, ProbeStatus::Skipped) => "Skipped (offline)".to_string(),
+            (None, _) => "Not available".to_string(),
+        }
+    }
+
+    /// Bandwidth for display, or why it is missing
+    pub fn bandwidth_display(&self) -> String {
+        match (self.bandwidth_mbps, self.bandwidth_status) {
+            (Some(bandwidth), _) => format!("{:.2} Mbps", bandwidth),
+            (None, ProbeStatus::Skipped) => "Skipped (offline)".to_string(),
+            (None, _) => "Not available".to_string(),
+        }
+    }
 }
 
 impl SystemInfo {
     /// Collect current system information
     pub fn collect() -> Self {
-        let mut sys = System::new_all();
-
-        // Refresh system information
-        sys.refresh_all();
-
-        let disks = Disks::new_with_refreshed_list();
-        let networks = Networks::new_with_refreshed_list();
-
-        let os_name = System::name().unwrap_or_else(|| "Unknown".to_string());
-        let os_version = System::os_version().unwrap_or_else(|| "Unknown".to_string());
-        let kernel_version = System::kernel_version().unwrap_or_else(|| "Unknown".to_string());
-        let hostname = System::host_name().unwrap_or_else(|| "Unknown".to_string());
-
-        let cpu_count = sys.cpus().len();
-        let cpu_brand = sys
-            .cpus()
-            .first()
-            .map(|cpu| cpu.brand().to_string())
-            .unwrap_or_else(|| "Unknown".to_string());
-
-        let total_memory = sys.total_memory();
-        let used_memory = sys.used_memory();
-        let total_swap = sys.total_swap();
-        let used_swap = sys.used_swap();
-
-        let disk_info: Vec<DiskInfo> = disks
-            .iter()
-            .map(|disk| DiskInfo {
-                name: disk.name().to_string_lossy().to_string(),
-                mount_point: disk.mount_point().to_string_lossy().to_string(),
-                total_space: disk.total_space(),
-                available_space: disk.available_space(),
-                file_system: disk.file_system().to_string_lossy().to_string(),
-            })
-            .collect();
-
-        let network_info: Vec<NetworkInfo> = networks
-            .iter()
-            .map(|(interface_name, data)| NetworkInfo {
-                interface_name: interface_name.clone(),
-                received_bytes: data.received(),
-                transmitted_bytes: data.transmitted(),
-                ip_address: None, // Interface-specific IPs not provided by sysinfo crate
-            })
-            .collect();
-
-        let processes_count = sys.processes().len();
-        let uptime = System::uptime();
-
-        let network_details = NetworkDetails {
-            local_ip: Self::get_local_ip(),
-            public_ip: Self::get_public_ip(),
-            bandwidth_mbps: Self::benchmark_bandwidth(),
-        };
+        Self::collect_with(&CollectOptions::default())
+    }
 
-        Self {
-            os_name,
-            os_version,
-            kernel_version,
-            hostname,
-            cpu_count,
-            cpu_brand,
-            total_memory,
-            used_memory,
-            total_swap,
-            used_swap,
-            disks: disk_info,
-            networks: network_info,
-            network_details,
-            processes_count,
-            uptime,
+    /// Collect the sections of system information selected by `options`
+    pub fn collect_with(options: &CollectOptions) -> Self {
+        let mut info = Self::default();
+        let mut sys = System::new();
+
+        // Disk I/O and network rates need two samples; take the first ones now
+        // so their windows overlap with the CPU sampling delay
+        let diskstats = Path::new(diskstats::PROC_DISKSTATS);
+        let first_disk_sample = (options.includes(Section::Disks)
+            && !options.disk_sample_interval.is_zero())
+        .then(|| (Instant::now(), DiskStatsSample::read(diskstats)));
+        let net_dev = Path::new(netdev::PROC_NET_DEV);
+        let first_net_sample = (options.includes(Section::Networks)
+            && !options.network_sample_interval.is_zero())
+        .then(|| (Instant::now(), NetDevSample::read(net_dev)));
+
+        if options.includes(Section::System) {
+            info.os_name = System::name().unwrap_or_else(|| "Unknown".to_string());
+            info.os_version = System::os_version().unwrap_or_else(|| "Unknown".to_string());
+            info.kernel_version = System::kernel_version().unwrap_or_else(|| "Unknown".to_string());
+            info.hostname = System::host_name().unwrap_or_else(|| "Unknown".to_string());
+            info.uptime = System::uptime();
+            info.environment = Environment::detect();
+        }
+
+        if options.includes(Section::Cpu) {
+            // Usage and time breakdown are computed between two samples
+            let proc_stat = Path::new(cpu::PROC_STAT);
+            let first_sample = CpuTimeSample::read(proc_stat);
+            sys.refresh_cpu();
+            std::thread::sleep(options.cpu_sample_interval);
+            sys.refresh_cpu();
+            let times = first_sample
+                .zip(CpuTimeSample::read(proc_stat))
+                .map(|(first, second)| {
+                    CpuTimes::between(
+                        &first,
+                        &second,
+                        options.cpu_sample_interval.as_millis() as u64,
+                    )
+                });
+            info.cpu = CpuDetails::from_system(&sys, Path::new(SYSFS_ROOT), times);
+            info.cpu_count = sys.cpus().len();
+            info.cpu_brand = sys
+                .cpus()
+                .first()
+                .map(|cpu| cpu.brand().to_string())
+                .unwrap_or_else(|| "Unknown".to_string());
+        }
+
+        if options.includes(Section::Memory) {
+            sys.refresh_memory();
+            info.total_memory = sys.total_memory();
+            info.used_memory = sys.used_memory();
+            info.total_swap = sys.total_swap();
+            info.used_swap = sys.used_swap();
+            info.memory = MemoryDetails::read(Path::new(PROC_ROOT), Path::new(SYSFS_ROOT));
+        }
+
+        if options.includes(Section::Disks) {
+            let disks = Disks::new_with_refreshed_list();
+            let mounts = MountEntry::read(Path::new(mountinfo::PROC_SELF_MOUNTINFO));
+            info.disks = disks
+                .iter()
+                .filter(|disk| {
+                    options.disk_filter.matches(
+                        &disk.file_system().to_string_lossy(),
+                        &disk.mount_point().to_string_lossy(),
+                    )
+                })
+                .map(|disk| {
+                    let mount_point = disk.mount_point().to_string_lossy().to_string();
+                    // The last entry wins when several filesystems are stacked on one path
+                    let mount = mounts.iter().rev().find(|m| m.mount_point == mount_point);
+                    DiskInfo {
+                        name: disk.name().to_string_lossy().to_string(),
+                        total_space: disk.total_space(),
+                        available_space: disk.available_space(),
+                        file_system: disk.file_system().to_string_lossy().to_string(),
+                        inodes: InodeUsage::read(disk.mount_point()),
+                        dev: mount.map(|m| m.dev.clone()),
+                        source: mount.map(|m| m.source.clone()),
+                        mount_options: mount.map(|m| m.mount_options.clone()).unwrap_or_default(),
+                        forecast: None,
+                        mount_point,
+                    }
+                })
+                .collect();
+
+            info.block_devices =
+                BlockDevice::read_tree(Path::new(SYSFS_ROOT), Path::new(PROC_ROOT));
+            info.raid_arrays = MdArray::read(Path::new(mdstat::PROC_MDSTAT));
+
+            if let Some((sampled_at, first)) = first_disk_sample {
+                std::thread::sleep(
+                    options
+                        .disk_sample_interval
+                        .saturating_sub(sampled_at.elapsed()),
+                );
+                let interval_ms = sampled_at.elapsed().as_millis() as u64;
+                info.disk_io =
+                    DiskIoStats::between(&first, &DiskStatsSample::read(diskstats), interval_ms);
+            }
+        }
+
+        if options.includes(Section::Networks) {
+            let networks = Networks::new_with_refreshed_list();
+            let mut addresses = InterfaceAddress::read_all();
+            let mut rates: HashMap<String, NetworkRates> = HashMap::new();
+            let latest = match first_net_sample {
+                Some((sampled_at, first)) => {
+                    std::thread::sleep(
+                        options
+                            .network_sample_interval
+                            .saturating_sub(sampled_at.elapsed()),
+                    );
+                    let interval_ms = sampled_at.elapsed().as_millis() as u64;
+                    let latest = NetDevSample::read(net_dev);
+                    for after in &latest {
+                        if let Some(before) = first.iter().find(|s| s.interface == after.interface)
+                        {
+                            rates.insert(
+                                after.interface.clone(),
+                                NetworkRates::between(before, after, interval_ms),
+                            );
+                        }
+                    }
+                    latest
+                }
+                None => NetDevSample::read(net_dev),
+            };
+            info.networks = networks
+                .iter()
+                .map(|(interface_name, data)| {
+                    let addresses = addresses.remove(interface_name).unwrap_or_default();
+                    let mut link = LinkInfo::read(Path::new(SYSFS_ROOT), interface_name);
+                    if link.mac_address.is_none() {
+                        let mac = data.mac_address();
+                        link.mac_address = (!mac.is_unspecified()).then(|| mac.to_string());
+                    }
+                    NetworkInfo {
+                        interface_name: interface_name.clone(),
+                        // A freshly listed `Networks` has no previous refresh, so
+                        // `received()` would be zero; report lifetime totals
+                        received_bytes: data.total_received(),
+                        transmitted_bytes: data.total_transmitted(),
+                        ip_address: addresses
+                            .iter()
+                            .find(|address| address.is_ipv4())
+                            .or(addresses.first())
+                            .map(|address| address.address.to_string()),
+                        addresses,
+                        link,
+                        rates: rates.remove(interface_name),
+                        stats: latest
+                            .iter()
+                            .find(|sample| &sample.interface == interface_name)
+                            .map(|sample| sample.stats),
+                    }
+                })
+                .collect();
+            info.networks
+                .sort_by(|a, b| a.interface_name.cmp(&b.interface_name));
+        }
+
+        if options.includes(Section::NetworkDetails) {
+            let mut details = NetworkDetails {
+                local_ip: Self::get_local_ip(),
+                ..NetworkDetails::default()
+            };
+            if options.probes_public_ip() {
+                details.public_ip =
+                    Self::get_public_ip(&options.public_ip_resolvers, options.public_ip_timeout);
+                details.public_ip_status = ProbeStatus::of(&details.public_ip);
+            }
+            if options.probes_bandwidth() {
+                details.bandwidth_mbps =
+                    Self::benchmark_bandwidth(&options.bandwidth_urls, options.bandwidth_timeout);
+                details.bandwidth_status = ProbeStatus::of(&details.bandwidth_mbps);
+            }
+            info.network_details = details;
+        }
+
+        if options.includes(Section::Processes) {
+            sys.refresh_processes();
+            info.processes_count = sys.processes().len();
+        }
+
+        if options.includes(Section::Pressure) {
+            info.pressure = Pressure::read(Path::new(pressure::PROC_PRESSURE_DIR));
+        }
+
+        if options.includes(Section::Cgroup) {
+            info.cgroup = CgroupLimits::read(Path::new(PROC_ROOT), Path::new(SYSFS_ROOT));
+        }
+
+        info
+    }
+
+    /// Copy the fields belonging to `sections` from `other` into `self`
+    pub fn merge_sections(&mut self, other: &SystemInfo, sections: &[Section]) {
+        for section in sections {
+            match section {
+                Section::System => {
+                    self.os_name = other.os_name.clone();
+                    self.os_version = other.os_version.clone();
+                    self.kernel_version = other.kernel_version.clone();
+                    self.hostname = other.hostname.clone();
+                    self.uptime = other.uptime;
+                    self.environment = other.environment;
+                }
+                Section::Cpu => {
+                    self.cpu_count = other.cpu_count;
+                    self.cpu_brand = other.cpu_brand.clone();
+                    self.cpu = other.cpu.clone();
+                }
+                Section::Memory => {
+                    self.total_memory = other.total_memory;
+                    self.used_memory = other.used_memory;
+                    self.total_swap = other.total_swap;
+                    self.used_swap = other.used_swap;
+                    self.memory = other.memory.clone();
+                }
+                Section::Disks => {
+                    self.disks = other.disks.clone();
+                    self.disk_io = other.disk_io.clone();
+                    self.block_devices = other.block_devices.clone();
+                    self.raid_arrays = other.raid_arrays.clone();
+                }
+                Section::Networks => self.networks = other.networks.clone(),
+                Section::NetworkDetails => self.network_details = other.network_details.clone(),
+                Section::Processes => self.processes_count = other.processes_count,
+                Section::Pressure => self.pressure = other.pressure.clone(),
+                Section::Cgroup => self.cgroup = other.cgroup.clone(),
+            }
         }
     }
 
@@ -162,64 +495,16 @@ impl SystemInfo {
         local_ip_address::local_ip().ok().map(|ip| ip.to_string())
     }
 
-    /// Get public IP address
-    pub fn get_public_ip() -> Option<String> {
-        // Try multiple services for reliability
-        let services = [
-            "https://api.ipify.org",
-            "https://ifconfig.me/ip",
-            "https://icanhazip.com",
-        ];
-
-        for service in &services {
-            if let Ok(response) = reqwest::blocking::Client::builder()
-                .timeout(std::time::Duration::from_secs(5))
-                .build()
-                .and_then(|client| client.get(*service).send())
-            {
-                if let Ok(ip) = response.text() {
-                    let ip = ip.trim().to_string();
-                    if !ip.is_empty() {
-                        return Some(ip);
-                    }
-                }
-            }
-        }
-        None
+    /// Get public IP address from the first resolver that answers,
+    /// allowing `timeout` per resolver
+    pub fn get_public_ip(resolvers: &[PublicIpResolver], timeout: Duration) -> Option<String> {
+        probes::resolve_public_ip(resolvers, timeout).map(|ip| ip.to_string())
     }
 
-    /// Benchmark network bandwidth
-    pub fn benchmark_bandwidth() -> Option<f64> {
-        // Download a test file from a fast CDN to measure bandwidth
-        let test_urls = [
-            "https://speed.cloudflare.com/__down?bytes=1000000", // ~976 KB test
-        ];
-
-        for url in &test_urls {
-            let start = std::time::Instant::now();
-
-            match reqwest::blocking::Client::builder()
-                .timeout(std::time::Duration::from_secs(10))
-                .build()
-                .and_then(|client| client.get(*url).send())
-            {
-                Ok(response) => {
-                    if let Ok(bytes) = response.bytes() {
-                        let duration = start.elapsed();
-                        let duration_secs = duration.as_secs_f64();
-
-                        if duration_secs > 0.0 {
-                            let bytes_count = bytes.len() as f64;
-                            // Calculate Mbps (megabits per second)
-                            let mbps = (bytes_count * 8.0) / (duration_secs * 1_000_000.0);
-                            return Some(mbps);
-                        }
-                    }
-                }
-                Err(_) => continue,
-            }
-        }
-        None
+    /// Benchmark network bandwidth against the first reachable URL,
+    /// giving up on each after `timeout`
+    pub fn benchmark_bandwidth(urls: &[String], timeout: Duration) -> Option<f64> {
+        probes::benchmark_bandwidth(urls, timeout)
     }
 }
 
@@ -238,6 +523,27 @@ mod tests {
         assert!(info.total_memory > 0);
     }
 
+    #[test]
+    fn test_merge_sections() {
+        let mut info = SystemInfo {
+            hostname: "old".to_string(),
+            cpu_count: 2,
+            ..SystemInfo::default()
+        };
+        let update = SystemInfo {
+            hostname: "new".to_string(),
+            cpu_count: 8,
+            total_memory: 1024,
+            ..SystemInfo::default()
+        };
+
+        info.merge_sections(&update, &[Section::Cpu, Section::Memory]);
+
+        assert_eq!(info.hostname, "old");
+        assert_eq!(info.cpu_count, 8);
+        assert_eq!(info.total_memory, 1024);
+    }
+
     #[test]
     fn test_format_bytes() {
         assert_eq!(SystemInfo::format_bytes(512), "512.00 B");
@@ -269,10 +575,36 @@ mod tests {
             local_ip: Some("192.168.1.1".to_string()),
             public_ip: Some("1.2.3.4".to_string()),
             bandwidth_mbps: Some(100.0),
+            ..NetworkDetails::default()
         };
 
         assert_eq!(details.local_ip, Some("192.168.1.1".to_string()));
         assert_eq!(details.public_ip, Some("1.2.3.4".to_string()));
         assert_eq!(details.bandwidth_mbps, Some(100.0));
     }
+
+    #[test]
+    fn test_network_details_display() {
+        let skipped = NetworkDetails::default();
+        assert_eq!(skipped.public_ip_display(), "Skipped (offline)");
+        assert_eq!(skipped.bandwidth_display(), "Skipped (offline)");
+
+        let unavailable = NetworkDetails {
+            public_ip_status: ProbeStatus::Unavailable,
+            bandwidth_status: ProbeStatus::Unavailable,
+            ..NetworkDetails::default()
+        };
+        assert_eq!(unavailable.public_ip_display(), "Not available");
+        assert_eq!(unavailable.bandwidth_display(), "Not available");
+
+        let found = NetworkDetails {
+            public_ip: Some("1.2.3.4".to_string()),
+            bandwidth_mbps: Some(12.345),
+            public_ip_status: ProbeStatus::Ok,
+            bandwidth_status: ProbeStatus::Ok,
+            ..NetworkDetails::default()
+        };
+        assert_eq!(found.public_ip_display(), "1.2.3.4");
+        assert_eq!(found.bandwidth_display(), "12.35 Mbps");
+    }
 }
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Mount table of the current process's mount namespace
+pub const PROC_SELF_MOUNTINFO: &str = "/proc/self/mountinfo";
+
+/// One line of `/proc/self/mountinfo`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MountEntry {
+    pub mount_id: u32,
+    pub parent_id: u32,
+    /// `major:minor` of the mounted device; `0:N` for filesystems without one
+    pub dev: String,
+    /// Path within the filesystem that forms the mount's root, `/` unless bind-mounted
+    pub root: String,
+    pub mount_point: String,
+    /// Per-mount options, e.g. `rw`, `nosuid`, `relatime`
+    pub mount_options: Vec<String>,
+    pub fs_type: String,
+    /// Filesystem-specific source, e.g. `/dev/sda1` or `tmpfs`
+    pub source: String,
+    /// Superblock options, e.g. `errors=remount-ro`
+    pub super_options: Vec<String>,
+}
+
+impl MountEntry {
+    /// Read a `/proc/<pid>/mountinfo` file
+    pub fn read(path: &Path) -> Vec<Self> {
+        std::fs::read_to_string(path)
+            .map(|contents| Self::parse(&contents))
+            .unwrap_or_default()
+    }
+
+    /// Parse `mountinfo` contents, skipping malformed lines
+    pub fn parse(contents: &str) -> Vec<Self> {
+        contents.lines().filter_map(Self::parse_line).collect()
+    }
+
+    /// `id parent major:minor root mount-point options [optional fields...] - type source super-options`
+    fn parse_line(line: &str) -> Option<Self> {
+        let (mount, filesystem) = line.split_once(" - ")?;
+        let mut fields = mount.split_whitespace();
+        let mount_id = fields.next()?.parse().ok()?;
+        let parent_id = fields.next()?.parse().ok()?;
+        let dev = fields.next()?.to_string();
+        let root = unescape(fields.next()?);
+        let mount_point = unescape(fields.next()?);
+        let mount_options = split_options(fields.next()?);
+
+        let mut fields = filesystem.split_whitespace();
+        let fs_type = fields.next()?.to_string();
+        let source = unescape(fields.next()?);
+        let super_options = fields.next().map(split_options).unwrap_or_default();
+
+        Some(Self {
+            mount_id,
+            parent_id,
+            dev,
+            root,
+            mount_point,
+            mount_options,
+            fs_type,
+            source,
+            super_options,
+        })
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.mount_options.iter().any(|option| option == "ro")
+    }
+}
+
+fn split_options(options: &str) -> Vec<String> {
+    options.split(',').map(str::to_string).collect()
+}
+
+/// Undo the kernel's octal escaping of space, tab, newline and backslash
+fn unescape(field: &str) -> String {
+    field
+        .replace("\\040", " ")
+        .replace("\\011", "\t")
+        .replace("\\012", "\n")
+        .replace("\\134", "\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixture_path;
+
+    #[test]
+    fn test_parse_fixture() {
+        let mounts = MountEntry::read(&fixture_path("proc/self/mountinfo"));
+        assert_eq!(mounts.len(), 11);
+
+        let root = mounts.iter().find(|m| m.mount_point == "/").unwrap();
+        assert_eq!(root.mount_id, 28);
+        assert_eq!(root.parent_id, 1);
+        assert_eq!(root.dev, "253:1");
+        assert_eq!(root.fs_type, "ext4");
+        assert_eq!(root.source, "/dev/mapper/vg0-root");
+        assert_eq!(root.mount_options, ["rw", "relatime"]);
+        assert_eq!(root.super_options, ["rw", "errors=remount-ro"]);
+        assert!(!root.is_read_only());
+
+        let usb = mounts.iter().find(|m| m.dev == "8:33").unwrap();
+        assert_eq!(usb.mount_point, "/media/usb stick");
+        assert_eq!(usb.mount_options, ["rw", "nosuid", "nodev", "relatime"]);
+    }
+
+    #[test]
+    fn test_optional_fields_and_read_only() {
+        let mounts = MountEntry::parse(
+            "1504 29 7:3 / /snap/core22/1380 ro,nodev,relatime shared:602 master:1 - squashfs /dev/loop3 ro,errors=continue\n\
+             garbage line\n",
+        );
+        assert_eq!(mounts.len(), 1);
+        assert_eq!(mounts[0].fs_type, "squashfs");
+        assert_eq!(mounts[0].source, "/dev/loop3");
+        assert!(mounts[0].is_read_only());
+    }
+}
@@ -0,0 +1,243 @@
+use super::{counter_delta, SystemInfo};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Per-interface network counters
+pub const PROC_NET_DEV: &str = "/proc/net/dev";
+
+/// Cumulative counters of one network interface from `/proc/net/dev`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetDevSample {
+    pub interface: String,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub stats: InterfaceStats,
+}
+
+/// Packet and fault counters of a network interface since it came up
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceStats {
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    /// Bad packets: CRC, framing and length errors on receive; aborted or late sends on transmit
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    /// Packets dropped for lack of buffers or by the kernel
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+    /// FIFO overruns, where the NIC ran out of ring buffer space
+    pub rx_overruns: u64,
+    pub tx_overruns: u64,
+    pub collisions: u64,
+    /// Multicast packets received
+    pub multicast: u64,
+}
+
+/// Throughput of one network interface between two samples
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NetworkRates {
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+    pub rx_packets_per_sec: f64,
+    pub tx_packets_per_sec: f64,
+    /// Receive and transmit errors
+    #[serde(default)]
+    pub errors_per_sec: f64,
+    /// Receive and transmit drops
+    #[serde(default)]
+    pub drops_per_sec: f64,
+    /// Time between the two samples
+    pub interval_ms: u64,
+}
+
+impl NetDevSample {
+    /// Read every interface from a `/proc/net/dev` file
+    pub fn read(path: &Path) -> Vec<Self> {
+        std::fs::read_to_string(path)
+            .map(|contents| Self::parse(&contents))
+            .unwrap_or_default()
+    }
+
+    /// Parse `/proc/net/dev` contents, skipping the two header lines
+    pub fn parse(contents: &str) -> Vec<Self> {
+        contents
+            .lines()
+            .filter_map(|line| {
+                // `  eth0: bytes packets errs drop fifo frame compressed multicast`
+                // for receive, then `bytes packets errs drop fifo colls carrier compressed`
+                // for transmit
+                let (interface, counters) = line.split_once(':')?;
+                let counters: Vec<u64> = counters
+                    .split_whitespace()
+                    .map(|value| value.parse().ok())
+                    .collect::<Option<_>>()?;
+                if counters.len() < 16 {
+                    return None;
+                }
+
+                Some(Self {
+                    interface: interface.trim().to_string(),
+                    rx_bytes: counters[0],
+                    tx_bytes: counters[8],
+                    stats: InterfaceStats {
+                        rx_packets: counters[1],
+                        tx_packets: counters[9],
+                        rx_errors: counters[2],
+                        tx_errors: counters[10],
+                        rx_dropped: counters[3],
+                        tx_dropped: counters[11],
+                        rx_overruns: counters[4],
+                        tx_overruns: counters[12],
+                        collisions: counters[13],
+                        multicast: counters[7],
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+impl NetworkRates {
+    pub fn between(before: &NetDevSample, after: &NetDevSample, interval_ms: u64) -> Self {
+        let per_second = |field: fn(&NetDevSample) -> u64| {
+            if interval_ms == 0 {
+                0.0
+            } else {
+                counter_delta(field(before), field(after)) as f64 * 1000.0 / interval_ms as f64
+            }
+        };
+
+        Self {
+            rx_bytes_per_sec: per_second(|s| s.rx_bytes),
+            tx_bytes_per_sec: per_second(|s| s.tx_bytes),
+            rx_packets_per_sec: per_second(|s| s.stats.rx_packets),
+            tx_packets_per_sec: per_second(|s| s.stats.tx_packets),
+            errors_per_sec: per_second(|s| s.stats.rx_errors + s.stats.tx_errors),
+            drops_per_sec: per_second(|s| s.stats.rx_dropped + s.stats.tx_dropped),
+            interval_ms,
+        }
+    }
+
+    /// Whether errors or drops occurred during the sampling window
+    pub fn has_faults(&self) -> bool {
+        self.errors_per_sec > 0.0 || self.drops_per_sec > 0.0
+    }
+
+    /// e.g. `↓ 1.20 MB/s (850 pkt/s)  ↑ 35.00 KB/s (410 pkt/s)`, plus any error and drop rates
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "↓ {}/s ({:.0} pkt/s)  ↑ {}/s ({:.0} pkt/s)",
+            SystemInfo::format_bytes(self.rx_bytes_per_sec as u64),
+            self.rx_packets_per_sec,
+            SystemInfo::format_bytes(self.tx_bytes_per_sec as u64),
+            self.tx_packets_per_sec
+        );
+        if self.has_faults() {
+            summary.push_str(&format!(
+                "  {:.1} errors/s, {:.1} drops/s",
+                self.errors_per_sec, self.drops_per_sec
+            ));
+        }
+        summary
+    }
+}
+
+impl InterfaceStats {
+    /// Labelled counters, e.g. `("Errors", "12 rx / 0 tx")`
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        let pair = |rx: u64, tx: u64| format!("{} rx / {} tx", rx, tx);
+        vec![
+            ("Packets", pair(self.rx_packets, self.tx_packets)),
+            ("Errors", pair(self.rx_errors, self.tx_errors)),
+            ("Dropped", pair(self.rx_dropped, self.tx_dropped)),
+            ("Overruns", pair(self.rx_overruns, self.tx_overruns)),
+            (
+                "Other",
+                format!(
+                    "{} collisions, {} multicast",
+                    self.collisions, self.multicast
+                ),
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixture_path;
+
+    #[test]
+    fn test_parse_fixture() {
+        let samples = NetDevSample::read(&fixture_path("proc/net/dev"));
+        let names: Vec<&str> = samples.iter().map(|s| s.interface.as_str()).collect();
+        assert_eq!(names, ["lo", "eth0", "wlan0", "docker0"]);
+
+        let eth0 = &samples[1];
+        assert_eq!(eth0.rx_bytes, 9_876_543_210);
+        assert_eq!(eth0.tx_bytes, 1_234_567_890);
+        assert_eq!(
+            eth0.stats.entries(),
+            [
+                ("Packets", "7654321 rx / 2345678 tx".to_string()),
+                ("Errors", "12 rx / 0 tx".to_string()),
+                ("Dropped", "40 rx / 5 tx".to_string()),
+                ("Overruns", "3 rx / 0 tx".to_string()),
+                ("Other", "0 collisions, 52310 multicast".to_string()),
+            ]
+        );
+        assert_eq!(samples[2].stats.collisions, 3);
+    }
+
+    #[test]
+    fn test_rates() {
+        let before = NetDevSample {
+            interface: "eth0".to_string(),
+            rx_bytes: 1_000_000,
+            tx_bytes: 500_000,
+            stats: InterfaceStats {
+                rx_packets: 1000,
+                tx_packets: 800,
+                ..InterfaceStats::default()
+            },
+        };
+        let mut after = NetDevSample {
+            rx_bytes: 1_000_000 + 2 * 1024 * 1024,
+            tx_bytes: 500_000 + 70 * 1024,
+            stats: InterfaceStats {
+                rx_packets: 2700,
+                tx_packets: 1620,
+                ..InterfaceStats::default()
+            },
+            ..before.clone()
+        };
+        let rates = NetworkRates::between(&before, &after, 2000);
+        assert_eq!(rates.rx_bytes_per_sec, 1024.0 * 1024.0);
+        assert_eq!(rates.tx_packets_per_sec, 410.0);
+        assert!(!rates.has_faults());
+        assert_eq!(
+            rates.summary(),
+            "↓ 1.00 MB/s (850 pkt/s)  ↑ 35.00 KB/s (410 pkt/s)"
+        );
+
+        after.stats.rx_errors = 3;
+        after.stats.tx_dropped = 1;
+        let faulty = NetworkRates::between(&before, &after, 2000);
+        assert!(faulty.has_faults());
+        assert!(faulty.summary().ends_with("  1.5 errors/s, 0.5 drops/s"));
+
+        // A re-created interface restarts its counters
+        let reset = NetworkRates::between(&after, &before, 1000);
+        assert_eq!(reset.rx_bytes_per_sec, 0.0);
+        assert_eq!(
+            NetworkRates::between(&before, &after, 0).rx_bytes_per_sec,
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_missing_or_malformed() {
+        assert!(NetDevSample::read(Path::new("/nonexistent/net/dev")).is_empty());
+        assert!(NetDevSample::parse("  eth0: 1 2 3\n").is_empty());
+    }
+}
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Directory holding the kernel's Pressure Stall Information files
+pub const PROC_PRESSURE_DIR: &str = "/proc/pressure";
+
+/// Pressure Stall Information for CPU, memory and I/O (Linux 4.20+)
+///
+/// A resource is `None` when the kernel does not expose it, e.g. when PSI
+/// is disabled with `psi=0`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pressure {
+    pub cpu: Option<ResourcePressure>,
+    pub memory: Option<ResourcePressure>,
+    pub io: Option<ResourcePressure>,
+}
+
+/// Stall figures for one resource
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourcePressure {
+    /// Time at least one task was stalled on the resource
+    pub some: PressureStall,
+    /// Time all non-idle tasks were stalled at once; absent for CPU before Linux 5.13
+    pub full: Option<PressureStall>,
+}
+
+/// Share of wall time spent stalled, averaged over 10s, 60s and 300s windows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PressureStall {
+    /// Percent
+    pub avg10: f64,
+    /// Percent
+    pub avg60: f64,
+    /// Percent
+    pub avg300: f64,
+    /// Cumulative stall time in microseconds
+    pub total_us: u64,
+}
+
+impl Pressure {
+    /// Read `cpu`, `memory` and `io` below `dir` (normally `/proc/pressure`)
+    pub fn read(dir: &Path) -> Self {
+        let read = |name: &str| {
+            std::fs::read_to_string(dir.join(name))
+                .ok()
+                .and_then(|contents| ResourcePressure::parse(&contents))
+        };
+        Self {
+            cpu: read("cpu"),
+            memory: read("memory"),
+            io: read("io"),
+        }
+    }
+
+    /// Whether any resource was read
+    pub fn is_available(&self) -> bool {
+        self.cpu.is_some() || self.memory.is_some() || self.io.is_some()
+    }
+
+    /// Resource names and their figures, skipping unavailable ones
+    pub fn entries(&self) -> Vec<(&'static str, ResourcePressure)> {
+        [("cpu", self.cpu), ("memory", self.memory), ("io", self.io)]
+            .into_iter()
+            .filter_map(|(name, pressure)| pressure.map(|p| (name, p)))
+            .collect()
+    }
+}
+
+impl ResourcePressure {
+    /// Parse the contents of a `/proc/pressure/*` file
+    pub fn parse(contents: &str) -> Option<Self> {
+        let mut some = None;
+        let mut full = None;
+        for line in contents.lines() {
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                Some("some") => some = PressureStall::parse(fields),
+                Some("full") => full = PressureStall::parse(fields),
+                _ => {}
+            }
+        }
+        Some(Self { some: some?, full })
+    }
+
+    /// One-line `avg10/avg60/avg300` summary, e.g. `some 1.20/0.80/0.30%  full 0.00/0.00/0.00%`
+    pub fn summary(&self) -> String {
+        let mut summary = format!("some {}", self.some.averages());
+        if let Some(full) = &self.full {
+            summary.push_str(&format!("  full {}", full.averages()));
+        }
+        summary
+    }
+}
+
+impl PressureStall {
+    /// `avg10/avg60/avg300` in percent
+    fn averages(&self) -> String {
+        format!("{:.2}/{:.2}/{:.2}%", self.avg10, self.avg60, self.avg300)
+    }
+
+    /// Parse `avg10=… avg60=… avg300=… total=…` fields
+    fn parse<'a>(fields: impl Iterator<Item = &'a str>) -> Option<Self> {
+        let mut stall = Self::default();
+        let mut seen = 0;
+        for field in fields {
+            let Some((key, value)) = field.split_once('=') else {
+                continue;
+            };
+            match key {
+                "avg10" => stall.avg10 = value.parse().ok()?,
+                "avg60" => stall.avg60 = value.parse().ok()?,
+                "avg300" => stall.avg300 = value.parse().ok()?,
+                "total" => stall.total_us = value.parse().ok()?,
+                _ => continue,
+            }
+            seen += 1;
+        }
+        (seen == 4).then_some(stall)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixture_path;
+
+    #[test]
+    fn test_parse_resource_pressure() {
+        let pressure = ResourcePressure::parse(
+            "some avg10=1.88 avg60=3.43 avg300=2.94 total=49102370\n\
+             full avg10=0.00 avg60=0.00 avg300=0.00 total=0\n",
+        )
+        .unwrap();
+        assert_eq!(pressure.some.avg10, 1.88);
+        assert_eq!(pressure.some.avg300, 2.94);
+        assert_eq!(pressure.some.total_us, 49_102_370);
+        assert_eq!(pressure.full.unwrap().total_us, 0);
+    }
+
+    #[test]
+    fn test_cpu_without_full_line() {
+        // Kernels before 5.13 only report "some" for CPU
+        let pressure =
+            ResourcePressure::parse("some avg10=0.50 avg60=0.25 avg300=0.10 total=1000\n").unwrap();
+        assert!(pressure.full.is_none());
+        assert!(ResourcePressure::parse("some avg10=0.50\n").is_none());
+    }
+
+    #[test]
+    fn test_read_fixture_pressure() {
+        let pressure = Pressure::read(&fixture_path("proc/pressure"));
+        assert!(pressure.is_available());
+        assert_eq!(pressure.cpu.unwrap().some.avg60, 12.5);
+        assert_eq!(pressure.memory.unwrap().full.unwrap().avg10, 4.2);
+        assert_eq!(pressure.io.unwrap().some.total_us, 987_654_321);
+        assert_eq!(pressure.entries().len(), 3);
+        assert_eq!(
+            pressure.memory.unwrap().summary(),
+            "some 8.10/3.00/1.20%  full 4.20/1.50/0.60%"
+        );
+
+        let missing = Pressure::read(Path::new("/nonexistent"));
+        assert!(!missing.is_available());
+        assert!(missing.entries().is_empty());
+    }
+}
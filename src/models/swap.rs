@@ -0,0 +1,284 @@
+use super::memory::selected_option;
+use super::{SystemInfo, SYSFS_BLOCK_DIR};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// zswap module parameters relative to the sysfs root
+const SYSFS_ZSWAP_DIR: &str = "module/zswap/parameters";
+
+/// An active swap area from `/proc/swaps`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwapDevice {
+    pub path: String,
+    /// `partition` or `file`
+    pub kind: String,
+    pub size_bytes: u64,
+    pub used_bytes: u64,
+    /// Higher priorities are used first
+    pub priority: i32,
+}
+
+/// A zram compressed RAM block device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZramDevice {
+    pub name: String,
+    /// Compression algorithm in use, e.g. `zstd`
+    pub algorithm: Option<String>,
+    /// Configured uncompressed capacity
+    pub disk_size_bytes: u64,
+    /// Uncompressed size of the data stored
+    pub original_bytes: u64,
+    /// Compressed size of the data stored
+    pub compressed_bytes: u64,
+    /// RAM consumed, including allocator overhead
+    pub memory_used_bytes: u64,
+}
+
+/// zswap compressed swap cache status
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ZswapStatus {
+    pub enabled: bool,
+    pub compressor: Option<String>,
+    /// Largest share of RAM the pool may take, in percent
+    pub max_pool_percent: Option<u32>,
+    /// RAM used by the compressed pool (`Zswap` in `/proc/meminfo`, Linux 6.5+)
+    pub pool_bytes: Option<u64>,
+    /// Uncompressed size of the pages in the pool (`Zswapped`)
+    pub stored_bytes: Option<u64>,
+}
+
+impl SwapDevice {
+    /// Read active swap areas from a `/proc/swaps` file
+    pub fn read(path: &Path) -> Vec<Self> {
+        std::fs::read_to_string(path)
+            .map(|contents| Self::parse(&contents))
+            .unwrap_or_default()
+    }
+
+    /// Parse `/proc/swaps` contents; sizes are reported in KiB
+    pub fn parse(contents: &str) -> Vec<Self> {
+        contents
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let [path, kind, size, used, priority] = fields[..] else {
+                    return None;
+                };
+                Some(Self {
+                    // Paths with spaces are escaped as \040
+                    path: path.replace("\\040", " "),
+                    kind: kind.to_string(),
+                    size_bytes: size.parse::<u64>().ok()? * 1024,
+                    used_bytes: used.parse::<u64>().ok()? * 1024,
+                    priority: priority.parse().ok()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Share of the area in use, in percent
+    pub fn usage_percent(&self) -> f64 {
+        if self.size_bytes == 0 {
+            0.0
+        } else {
+            self.used_bytes as f64 / self.size_bytes as f64 * 100.0
+        }
+    }
+
+    /// One-line summary, e.g. `/dev/sda2 (partition, prio -2): 1.00 GB / 8.00 GB (12.5%)`
+    pub fn summary(&self) -> String {
+        format!(
+            "{} ({}, prio {}): {} / {} ({:.1}%)",
+            self.path,
+            self.kind,
+            self.priority,
+            SystemInfo::format_bytes(self.used_bytes),
+            SystemInfo::format_bytes(self.size_bytes),
+            self.usage_percent()
+        )
+    }
+}
+
+impl ZramDevice {
+    /// Read every `zram*` device below `sysfs_root`
+    pub fn read_all(sysfs_root: &Path) -> Vec<Self> {
+        let Ok(entries) = std::fs::read_dir(sysfs_root.join(SYSFS_BLOCK_DIR)) else {
+            return Vec::new();
+        };
+
+        let mut devices: Vec<Self> = entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let name = entry.file_name().to_str()?.to_string();
+                if !name.starts_with("zram") {
+                    return None;
+                }
+                Self::read(name, &entry.path())
+            })
+            .collect();
+        devices.sort_by(|a, b| a.name.cmp(&b.name));
+        devices
+    }
+
+    /// Read one device; `None` for devices that were never initialized
+    fn read(name: String, dir: &Path) -> Option<Self> {
+        let read = |file: &str| std::fs::read_to_string(dir.join(file)).ok();
+        let disk_size_bytes: u64 = read("disksize")?.trim().parse().ok()?;
+        if disk_size_bytes == 0 {
+            return None;
+        }
+        // mm_stat: orig_data_size compr_data_size mem_used_total ...
+        let mm_stat: Vec<u64> = read("mm_stat")?
+            .split_whitespace()
+            .filter_map(|value| value.parse().ok())
+            .collect();
+
+        Some(Self {
+            name,
+            algorithm: read("comp_algorithm").and_then(|contents| selected_option(&contents)),
+            disk_size_bytes,
+            original_bytes: *mm_stat.first()?,
+            compressed_bytes: *mm_stat.get(1)?,
+            memory_used_bytes: *mm_stat.get(2)?,
+        })
+    }
+
+    /// Uncompressed size divided by compressed size; `None` while empty
+    pub fn compression_ratio(&self) -> Option<f64> {
+        (self.compressed_bytes > 0)
+            .then(|| self.original_bytes as f64 / self.compressed_bytes as f64)
+    }
+
+    /// One-line summary, e.g. `zram0 (zstd, 4.00 GB): 400.00 MB → 100.00 MB, ratio 4.00x, 105.00 MB RAM`
+    pub fn summary(&self) -> String {
+        format!(
+            "{} ({}, {}): {} → {}, ratio {}, {} RAM",
+            self.name,
+            self.algorithm.as_deref().unwrap_or("unknown"),
+            SystemInfo::format_bytes(self.disk_size_bytes),
+            SystemInfo::format_bytes(self.original_bytes),
+            SystemInfo::format_bytes(self.compressed_bytes),
+            format_ratio(self.compression_ratio()),
+            SystemInfo::format_bytes(self.memory_used_bytes)
+        )
+    }
+}
+
+impl ZswapStatus {
+    /// Read zswap parameters below `sysfs_root` and pool sizes from `/proc/meminfo` fields
+    ///
+    /// Returns `None` when the zswap module is not present.
+    pub fn read(sysfs_root: &Path, meminfo: &HashMap<String, u64>) -> Option<Self> {
+        let dir = sysfs_root.join(SYSFS_ZSWAP_DIR);
+        let read = |file: &str| {
+            std::fs::read_to_string(dir.join(file))
+                .ok()
+                .map(|value| value.trim().to_string())
+        };
+
+        Some(Self {
+            enabled: matches!(read("enabled")?.as_str(), "Y" | "1"),
+            compressor: read("compressor"),
+            max_pool_percent: read("max_pool_percent").and_then(|value| value.parse().ok()),
+            pool_bytes: meminfo.get("Zswap").copied(),
+            stored_bytes: meminfo.get("Zswapped").copied(),
+        })
+    }
+
+    /// Uncompressed size divided by pool size; `None` while empty or unknown
+    pub fn compression_ratio(&self) -> Option<f64> {
+        match (self.stored_bytes, self.pool_bytes) {
+            (Some(stored), Some(pool)) if pool > 0 => Some(stored as f64 / pool as f64),
+            _ => None,
+        }
+    }
+
+    /// One-line summary, e.g. `enabled, lz4, max pool 20%, pool 100.00 MB holding 300.00 MB (ratio 3.00x)`
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{}, {}",
+            if self.enabled { "enabled" } else { "disabled" },
+            self.compressor.as_deref().unwrap_or("unknown compressor")
+        );
+        if let Some(percent) = self.max_pool_percent {
+            summary.push_str(&format!(", max pool {}%", percent));
+        }
+        if let (Some(pool), Some(stored)) = (self.pool_bytes, self.stored_bytes) {
+            summary.push_str(&format!(
+                ", pool {} holding {} (ratio {})",
+                SystemInfo::format_bytes(pool),
+                SystemInfo::format_bytes(stored),
+                format_ratio(self.compression_ratio())
+            ));
+        }
+        summary
+    }
+}
+
+fn format_ratio(ratio: Option<f64>) -> String {
+    ratio.map_or_else(|| "n/a".to_string(), |ratio| format!("{:.2}x", ratio))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixture_path;
+
+    const PROC_SWAPS: &str = "Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority
+/dev/nvme0n1p3                          partition\t8388604\t\t1048576\t\t-2
+/swap\\040file                           file\t\t2097148\t\t0\t\t-3
+/dev/zram0                              partition\t4194304\t\t524288\t\t100
+";
+
+    #[test]
+    fn test_parse_proc_swaps() {
+        let devices = SwapDevice::parse(PROC_SWAPS);
+        assert_eq!(devices.len(), 3);
+        assert_eq!(devices[0].path, "/dev/nvme0n1p3");
+        assert_eq!(devices[0].used_bytes, 1_048_576 * 1024);
+        assert_eq!(devices[0].priority, -2);
+        assert_eq!(devices[1].path, "/swap file");
+        assert_eq!(devices[1].kind, "file");
+        assert_eq!(devices[2].priority, 100);
+        assert_eq!(devices[2].usage_percent(), 12.5);
+
+        assert!(SwapDevice::parse("Filename Type Size Used Priority\n").is_empty());
+    }
+
+    #[test]
+    fn test_read_fixture_zram() {
+        let devices = ZramDevice::read_all(&fixture_path("sysfs"));
+        assert_eq!(devices.len(), 1);
+
+        let zram = &devices[0];
+        assert_eq!(zram.name, "zram0");
+        assert_eq!(zram.algorithm.as_deref(), Some("zstd"));
+        assert_eq!(zram.disk_size_bytes, 4_294_967_296);
+        assert_eq!(zram.compression_ratio(), Some(4.0));
+        assert_eq!(
+            zram.summary(),
+            "zram0 (zstd, 4.00 GB): 400.00 MB → 100.00 MB, ratio 4.00x, 105.00 MB RAM"
+        );
+    }
+
+    #[test]
+    fn test_read_fixture_zswap() {
+        let meminfo = HashMap::from([
+            ("Zswap".to_string(), 100 * 1024),
+            ("Zswapped".to_string(), 300 * 1024),
+        ]);
+        let zswap = ZswapStatus::read(&fixture_path("sysfs"), &meminfo).unwrap();
+        assert!(zswap.enabled);
+        assert_eq!(zswap.compressor.as_deref(), Some("lz4"));
+        assert_eq!(zswap.max_pool_percent, Some(20));
+        assert_eq!(zswap.compression_ratio(), Some(3.0));
+        assert_eq!(
+            zswap.summary(),
+            "enabled, lz4, max pool 20%, pool 100.00 KB holding 300.00 KB (ratio 3.00x)"
+        );
+
+        assert!(ZswapStatus::read(Path::new("/nonexistent"), &meminfo).is_none());
+    }
+}
@@ -0,0 +1,401 @@
+use super::cpu::SYSFS_CPU_DIR;
+use super::SystemInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// NUMA node directory relative to the sysfs root
+const SYSFS_NODE_DIR: &str = "devices/system/node";
+
+/// Physical layout of the CPUs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuTopology {
+    pub packages: Vec<CpuPackage>,
+    pub numa_nodes: Vec<NumaNode>,
+    /// Distinct cache instances
+    pub caches: Vec<CpuCache>,
+}
+
+/// A physical CPU package (socket)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuPackage {
+    pub id: u32,
+    pub cores: Vec<PhysicalCore>,
+}
+
+/// A physical core and the logical CPUs (SMT threads) running on it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhysicalCore {
+    pub id: u32,
+    pub threads: Vec<usize>,
+}
+
+/// A NUMA node and its logical CPUs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NumaNode {
+    pub id: u32,
+    pub cpus: Vec<usize>,
+}
+
+/// One cache instance and the logical CPUs sharing it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuCache {
+    pub level: u8,
+    /// `Data`, `Instruction` or `Unified`
+    pub cache_type: String,
+    pub size_bytes: u64,
+    pub shared_cpus: Vec<usize>,
+}
+
+impl CpuCache {
+    /// Short name such as `L1d`, `L1i` or `L3`
+    pub fn name(&self) -> String {
+        let suffix = match self.cache_type.as_str() {
+            "Data" => "d",
+            "Instruction" => "i",
+            _ => "",
+        };
+        format!("L{}{}", self.level, suffix)
+    }
+}
+
+impl CpuTopology {
+    /// Read the topology below `sysfs_root` (normally `/sys`)
+    ///
+    /// Returns `None` when the CPU topology directories are missing.
+    pub fn read(sysfs_root: &Path) -> Option<Self> {
+        let cpu_dir = sysfs_root.join(SYSFS_CPU_DIR);
+        let mut cpus: Vec<usize> = std::fs::read_dir(&cpu_dir)
+            .ok()?
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name();
+                name.to_str()?.strip_prefix("cpu")?.parse().ok()
+            })
+            .collect();
+        cpus.sort_unstable();
+
+        // package id -> core id -> threads
+        let mut layout: BTreeMap<u32, BTreeMap<u32, Vec<usize>>> = BTreeMap::new();
+        let mut caches: Vec<CpuCache> = Vec::new();
+
+        for cpu in cpus {
+            let dir = cpu_dir.join(format!("cpu{}", cpu));
+            let topology = dir.join("topology");
+            let (Some(package), Some(core)) = (
+                read_value::<u32>(&topology.join("physical_package_id")),
+                read_value::<u32>(&topology.join("core_id")),
+            ) else {
+                continue;
+            };
+            layout
+                .entry(package)
+                .or_default()
+                .entry(core)
+                .or_default()
+                .push(cpu);
+
+            for cache in read_caches(&dir.join("cache")) {
+                if !caches.contains(&cache) {
+                    caches.push(cache);
+                }
+            }
+        }
+
+        if layout.is_empty() {
+            return None;
+        }
+
+        let packages = layout
+            .into_iter()
+            .map(|(id, cores)| CpuPackage {
+                id,
+                cores: cores
+                    .into_iter()
+                    .map(|(id, threads)| PhysicalCore { id, threads })
+                    .collect(),
+            })
+            .collect();
+
+        caches.sort_by(|a, b| {
+            (a.level, &a.cache_type, &a.shared_cpus).cmp(&(b.level, &b.cache_type, &b.shared_cpus))
+        });
+
+        Some(Self {
+            packages,
+            numa_nodes: read_numa_nodes(&sysfs_root.join(SYSFS_NODE_DIR)),
+            caches,
+        })
+    }
+
+    /// Number of physical cores across all packages
+    pub fn physical_core_count(&self) -> usize {
+        self.packages.iter().map(|p| p.cores.len()).sum()
+    }
+
+    /// Number of logical CPUs across all packages
+    pub fn logical_cpu_count(&self) -> usize {
+        self.packages
+            .iter()
+            .flat_map(|p| &p.cores)
+            .map(|c| c.threads.len())
+            .sum()
+    }
+
+    /// Most SMT threads found on any core
+    pub fn threads_per_core(&self) -> usize {
+        self.packages
+            .iter()
+            .flat_map(|p| &p.cores)
+            .map(|c| c.threads.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// NUMA node containing a logical CPU
+    pub fn numa_node_of(&self, cpu: usize) -> Option<u32> {
+        self.numa_nodes
+            .iter()
+            .find(|node| node.cpus.contains(&cpu))
+            .map(|node| node.id)
+    }
+
+    /// Render the topology as an indented tree, one line per package and core
+    ///
+    /// Caches private to a core are listed on the core; wider caches on the package.
+    pub fn tree_lines(&self) -> Vec<String> {
+        let mut lines = vec![format!(
+            "{} package(s), {} core(s), {} thread(s) per core, {} NUMA node(s)",
+            self.packages.len(),
+            self.physical_core_count(),
+            self.threads_per_core(),
+            self.numa_nodes.len()
+        )];
+
+        for package in &self.packages {
+            let package_cpus: Vec<usize> = package
+                .cores
+                .iter()
+                .flat_map(|c| c.threads.iter().copied())
+                .collect();
+            let shared = self.cache_labels(|cache| {
+                is_subset(&cache.shared_cpus, &package_cpus)
+                    && !package
+                        .cores
+                        .iter()
+                        .any(|core| is_subset(&cache.shared_cpus, &core.threads))
+            });
+            lines.push(format!("Package {}{}", package.id, shared));
+
+            for (index, core) in package.cores.iter().enumerate() {
+                let branch = if index + 1 == package.cores.len() {
+                    "└──"
+                } else {
+                    "├──"
+                };
+                let node = core
+                    .threads
+                    .first()
+                    .and_then(|cpu| self.numa_node_of(*cpu))
+                    .map(|node| format!(" [node {}]", node))
+                    .unwrap_or_default();
+                let private =
+                    self.cache_labels(|cache| is_subset(&cache.shared_cpus, &core.threads));
+                let threads: Vec<String> =
+                    core.threads.iter().map(|t| format!("cpu{}", t)).collect();
+                lines.push(format!(
+                    "{} Core {}{}{}: {}",
+                    branch,
+                    core.id,
+                    node,
+                    private,
+                    threads.join(", ")
+                ));
+            }
+        }
+
+        lines
+    }
+
+    /// ` (L1d 32.00 KB, L2 1.00 MB)` for the distinct cache kinds matching `filter`
+    fn cache_labels(&self, filter: impl Fn(&CpuCache) -> bool) -> String {
+        let mut labels: Vec<String> = Vec::new();
+        for cache in self.caches.iter().filter(|cache| filter(cache)) {
+            let label = format!(
+                "{} {}",
+                cache.name(),
+                SystemInfo::format_bytes(cache.size_bytes)
+            );
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        if labels.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", labels.join(", "))
+        }
+    }
+}
+
+/// Parse a kernel CPU list such as `0-3,8,10-11`
+pub fn parse_cpu_list(list: &str) -> Vec<usize> {
+    let mut cpus = Vec::new();
+    for part in list.trim().split(',').filter(|part| !part.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                if let (Ok(start), Ok(end)) = (start.parse::<usize>(), end.parse::<usize>()) {
+                    cpus.extend(start..=end);
+                }
+            }
+            None => {
+                if let Ok(cpu) = part.parse() {
+                    cpus.push(cpu);
+                }
+            }
+        }
+    }
+    cpus
+}
+
+/// Parse a sysfs cache size such as `32K` or `8M` into bytes
+fn parse_cache_size(size: &str) -> Option<u64> {
+    let size = size.trim();
+    let (number, multiplier) = match size.chars().last()? {
+        'K' => (&size[..size.len() - 1], 1024),
+        'M' => (&size[..size.len() - 1], 1024 * 1024),
+        'G' => (&size[..size.len() - 1], 1024 * 1024 * 1024),
+        _ => (size, 1),
+    };
+    number.parse::<u64>().ok().map(|n| n * multiplier)
+}
+
+fn read_value<T: std::str::FromStr>(path: &Path) -> Option<T> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn read_caches(cache_dir: &Path) -> Vec<CpuCache> {
+    let Ok(entries) = std::fs::read_dir(cache_dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if !path.file_name()?.to_str()?.starts_with("index") {
+                return None;
+            }
+            Some(CpuCache {
+                level: read_value(&path.join("level"))?,
+                cache_type: std::fs::read_to_string(path.join("type"))
+                    .ok()?
+                    .trim()
+                    .to_string(),
+                size_bytes: parse_cache_size(&std::fs::read_to_string(path.join("size")).ok()?)?,
+                shared_cpus: parse_cpu_list(
+                    &std::fs::read_to_string(path.join("shared_cpu_list")).ok()?,
+                ),
+            })
+        })
+        .collect()
+}
+
+fn read_numa_nodes(node_dir: &Path) -> Vec<NumaNode> {
+    let Ok(entries) = std::fs::read_dir(node_dir) else {
+        return Vec::new();
+    };
+
+    let mut nodes: Vec<NumaNode> = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let id = entry
+                .file_name()
+                .to_str()?
+                .strip_prefix("node")?
+                .parse()
+                .ok()?;
+            let cpus = std::fs::read_to_string(entry.path().join("cpulist")).ok()?;
+            Some(NumaNode {
+                id,
+                cpus: parse_cpu_list(&cpus),
+            })
+        })
+        .collect();
+    nodes.sort_by_key(|node| node.id);
+    nodes
+}
+
+fn is_subset(inner: &[usize], outer: &[usize]) -> bool {
+    !inner.is_empty() && inner.iter().all(|cpu| outer.contains(cpu))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixture_path;
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list("5"), vec![5]);
+        assert!(parse_cpu_list("").is_empty());
+    }
+
+    #[test]
+    fn test_parse_cache_size() {
+        assert_eq!(parse_cache_size("32K\n"), Some(32 * 1024));
+        assert_eq!(parse_cache_size("8M"), Some(8 * 1024 * 1024));
+        assert_eq!(parse_cache_size("512"), Some(512));
+        assert_eq!(parse_cache_size("big"), None);
+    }
+
+    #[test]
+    fn test_read_fixture_topology() {
+        let topology = CpuTopology::read(&fixture_path("sysfs")).unwrap();
+
+        assert_eq!(topology.packages.len(), 2);
+        assert_eq!(topology.physical_core_count(), 4);
+        assert_eq!(topology.logical_cpu_count(), 8);
+        assert_eq!(topology.threads_per_core(), 2);
+        assert_eq!(
+            topology.packages[1].cores[0],
+            PhysicalCore {
+                id: 0,
+                threads: vec![2, 6]
+            }
+        );
+
+        assert_eq!(topology.numa_nodes.len(), 2);
+        assert_eq!(topology.numa_node_of(6), Some(1));
+
+        // Three private caches (L1d, L1i, L2) for each of four cores, plus one L3 per package
+        assert_eq!(topology.caches.len(), 4 * 3 + 2);
+        let l3: Vec<&CpuCache> = topology.caches.iter().filter(|c| c.level == 3).collect();
+        assert_eq!(l3[0].size_bytes, 16 * 1024 * 1024);
+        assert_eq!(l3[0].shared_cpus, vec![0, 1, 4, 5]);
+    }
+
+    #[test]
+    fn test_tree_lines() {
+        let topology = CpuTopology::read(&fixture_path("sysfs")).unwrap();
+        let lines = topology.tree_lines();
+
+        assert_eq!(
+            lines[0],
+            "2 package(s), 4 core(s), 2 thread(s) per core, 2 NUMA node(s)"
+        );
+        assert_eq!(lines[1], "Package 0 (L3 16.00 MB)");
+        assert_eq!(
+            lines[2],
+            "├── Core 0 [node 0] (L1d 32.00 KB, L1i 32.00 KB, L2 1.00 MB): cpu0, cpu4"
+        );
+        assert_eq!(
+            lines[6],
+            "└── Core 1 [node 1] (L1d 32.00 KB, L1i 32.00 KB, L2 1.00 MB): cpu3, cpu7"
+        );
+    }
+
+    #[test]
+    fn test_missing_sysfs() {
+        assert!(CpuTopology::read(Path::new("/nonexistent")).is_none());
+    }
+}
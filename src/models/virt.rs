@@ -0,0 +1,429 @@
+use super::features::CpuFeatures;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// DMI identification files relative to the filesystem root
+const DMI_DIR: &str = "sys/class/dmi/id";
+
+/// Hypervisor and container runtime the system runs under
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Environment {
+    /// `None` on bare metal
+    pub hypervisor: Option<Hypervisor>,
+    /// `None` outside containers
+    pub container: Option<ContainerRuntime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hypervisor {
+    Kvm,
+    Qemu,
+    Xen,
+    Vmware,
+    HyperV,
+    VirtualBox,
+    Firecracker,
+    /// The CPU reports a hypervisor we do not recognize
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+    Lxc,
+    Kubernetes,
+    SystemdNspawn,
+    Wsl,
+    /// A container marker we do not recognize
+    Unknown,
+}
+
+/// Raw signals used for detection, gathered from the filesystem and process environment
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DetectionHints {
+    /// DMI `sys_vendor`, `product_name`, `bios_vendor` and `board_vendor`
+    pub dmi: Vec<String>,
+    /// Contents of `/sys/hypervisor/type`
+    pub hypervisor_type: Option<String>,
+    /// Hypervisor vendor from CPUID leaf `0x40000000`, e.g. `KVMKVMKVM`
+    pub cpuid_vendor: Option<String>,
+    /// The `hypervisor` flag in `/proc/cpuinfo`
+    pub hypervisor_flag: bool,
+    /// Contents of `/proc/1/cgroup`
+    pub init_cgroup: String,
+    /// `/.dockerenv` exists
+    pub dockerenv: bool,
+    /// `/run/.containerenv` exists
+    pub containerenv: bool,
+    /// The `container` environment variable set by LXC, systemd-nspawn and others
+    pub container_var: Option<String>,
+    /// `KUBERNETES_SERVICE_HOST` is set
+    pub kubernetes_var: bool,
+    /// `WSL_DISTRO_NAME` is set
+    pub wsl_var: bool,
+    /// Contents of `/proc/sys/kernel/osrelease`
+    pub kernel_release: String,
+}
+
+impl Environment {
+    /// Detect the environment of the running system
+    pub fn detect() -> Self {
+        Self::from_hints(&DetectionHints::read(Path::new("/")).with_process_env())
+    }
+
+    /// Classify detection hints
+    pub fn from_hints(hints: &DetectionHints) -> Self {
+        Self {
+            hypervisor: detect_hypervisor(hints),
+            container: detect_container(hints),
+        }
+    }
+
+    pub fn is_bare_metal(&self) -> bool {
+        self.hypervisor.is_none() && self.container.is_none()
+    }
+
+    /// Human-readable summary, e.g. `Docker container in a KVM virtual machine`
+    pub fn describe(&self) -> String {
+        match (self.hypervisor, self.container) {
+            (None, None) => "Bare metal".to_string(),
+            (Some(hypervisor), None) => format!("{} virtual machine", hypervisor),
+            (None, Some(container)) => format!("{} container", container),
+            (Some(hypervisor), Some(container)) => {
+                format!(
+                    "{} container in a {} virtual machine",
+                    container, hypervisor
+                )
+            }
+        }
+    }
+}
+
+impl DetectionHints {
+    /// Gather file-based hints below `root` (normally `/`)
+    pub fn read(root: &Path) -> Self {
+        let read = |path: &str| {
+            std::fs::read_to_string(root.join(path))
+                .ok()
+                .map(|contents| contents.trim().to_string())
+        };
+        let dmi = ["sys_vendor", "product_name", "bios_vendor", "board_vendor"]
+            .iter()
+            .filter_map(|file| read(&format!("{}/{}", DMI_DIR, file)))
+            .filter(|value| !value.is_empty())
+            .collect();
+        let (flags, _) = CpuFeatures::parse_cpuinfo(&read("proc/cpuinfo").unwrap_or_default());
+
+        Self {
+            dmi,
+            hypervisor_type: read("sys/hypervisor/type"),
+            cpuid_vendor: None,
+            hypervisor_flag: flags.iter().any(|flag| flag == "hypervisor"),
+            init_cgroup: read("proc/1/cgroup").unwrap_or_default(),
+            dockerenv: root.join(".dockerenv").exists(),
+            containerenv: root.join("run/.containerenv").exists(),
+            container_var: read("proc/1/environ").and_then(|environ| {
+                environ
+                    .split('\0')
+                    .find_map(|var| var.strip_prefix("container="))
+                    .map(str::to_string)
+            }),
+            kubernetes_var: false,
+            wsl_var: false,
+            kernel_release: read("proc/sys/kernel/osrelease").unwrap_or_default(),
+        }
+    }
+
+    /// Add hints from this process's environment variables and the CPU
+    pub fn with_process_env(mut self) -> Self {
+        if self.container_var.is_none() {
+            self.container_var = std::env::var("container").ok();
+        }
+        self.kubernetes_var = std::env::var_os("KUBERNETES_SERVICE_HOST").is_some();
+        self.wsl_var = std::env::var_os("WSL_DISTRO_NAME").is_some();
+        self.cpuid_vendor = cpuid_hypervisor_vendor();
+        self
+    }
+}
+
+fn detect_hypervisor(hints: &DetectionHints) -> Option<Hypervisor> {
+    if let Some(vendor) = &hints.cpuid_vendor {
+        let hypervisor = match vendor.as_str() {
+            // Firecracker exposes no DMI tables, unlike QEMU and cloud KVM hosts
+            "KVMKVMKVM" if hints.dmi.is_empty() => Some(Hypervisor::Firecracker),
+            "KVMKVMKVM" => Some(Hypervisor::Kvm),
+            "TCGTCGTCGTCG" => Some(Hypervisor::Qemu),
+            "Microsoft Hv" => Some(Hypervisor::HyperV),
+            "VMwareVMware" => Some(Hypervisor::Vmware),
+            "XenVMMXenVMM" => Some(Hypervisor::Xen),
+            "VBoxVBoxVBox" => Some(Hypervisor::VirtualBox),
+            _ => None,
+        };
+        if hypervisor.is_some() {
+            return hypervisor;
+        }
+    }
+
+    let dmi = hints.dmi.join("\n");
+    let dmi_matches = [
+        ("Firecracker", Hypervisor::Firecracker),
+        ("KVM", Hypervisor::Kvm),
+        ("Amazon EC2", Hypervisor::Kvm),
+        ("Google Compute Engine", Hypervisor::Kvm),
+        ("QEMU", Hypervisor::Qemu),
+        ("VMware", Hypervisor::Vmware),
+        ("innotek GmbH", Hypervisor::VirtualBox),
+        ("VirtualBox", Hypervisor::VirtualBox),
+        ("Xen", Hypervisor::Xen),
+    ];
+    if let Some((_, hypervisor)) = dmi_matches.iter().find(|(needle, _)| dmi.contains(needle)) {
+        return Some(*hypervisor);
+    }
+    if dmi.contains("Microsoft Corporation") && dmi.contains("Virtual Machine") {
+        return Some(Hypervisor::HyperV);
+    }
+
+    if hints.hypervisor_type.as_deref() == Some("xen") {
+        return Some(Hypervisor::Xen);
+    }
+    hints.hypervisor_flag.then_some(Hypervisor::Unknown)
+}
+
+fn detect_container(hints: &DetectionHints) -> Option<ContainerRuntime> {
+    let release = hints.kernel_release.to_lowercase();
+    if hints.wsl_var || release.contains("microsoft") || release.contains("wsl") {
+        return Some(ContainerRuntime::Wsl);
+    }
+    if hints.kubernetes_var || hints.init_cgroup.contains("kubepods") {
+        return Some(ContainerRuntime::Kubernetes);
+    }
+    if let Some(var) = hints.container_var.as_deref().filter(|var| !var.is_empty()) {
+        return Some(match var {
+            "docker" => ContainerRuntime::Docker,
+            "podman" => ContainerRuntime::Podman,
+            "lxc" | "lxc-libvirt" => ContainerRuntime::Lxc,
+            "systemd-nspawn" => ContainerRuntime::SystemdNspawn,
+            _ => ContainerRuntime::Unknown,
+        });
+    }
+    if hints.containerenv {
+        return Some(ContainerRuntime::Podman);
+    }
+    if hints.dockerenv {
+        return Some(ContainerRuntime::Docker);
+    }
+
+    let cgroup = &hints.init_cgroup;
+    if cgroup.contains("/docker") {
+        Some(ContainerRuntime::Docker)
+    } else if cgroup.contains("libpod") {
+        Some(ContainerRuntime::Podman)
+    } else if cgroup.contains("/lxc") {
+        Some(ContainerRuntime::Lxc)
+    } else {
+        None
+    }
+}
+
+/// Hypervisor vendor signature from CPUID, when the CPU reports running under one
+#[cfg(target_arch = "x86_64")]
+fn cpuid_hypervisor_vendor() -> Option<String> {
+    use std::arch::x86_64::__cpuid;
+
+    // Bit 31 of ECX in leaf 1 is reserved for hypervisors to announce themselves
+    if __cpuid(1).ecx & (1 << 31) == 0 {
+        return None;
+    }
+    let leaf = __cpuid(0x4000_0000);
+    let bytes: Vec<u8> = [leaf.ebx, leaf.ecx, leaf.edx]
+        .iter()
+        .flat_map(|register| register.to_le_bytes())
+        .collect();
+    let vendor = String::from_utf8_lossy(&bytes)
+        .trim_end_matches('\0')
+        .to_string();
+    (!vendor.is_empty()).then_some(vendor)
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+fn cpuid_hypervisor_vendor() -> Option<String> {
+    None
+}
+
+impl std::fmt::Display for Hypervisor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Hypervisor::Kvm => "KVM",
+            Hypervisor::Qemu => "QEMU",
+            Hypervisor::Xen => "Xen",
+            Hypervisor::Vmware => "VMware",
+            Hypervisor::HyperV => "Hyper-V",
+            Hypervisor::VirtualBox => "VirtualBox",
+            Hypervisor::Firecracker => "Firecracker",
+            Hypervisor::Unknown => "unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::fmt::Display for ContainerRuntime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ContainerRuntime::Docker => "Docker",
+            ContainerRuntime::Podman => "Podman",
+            ContainerRuntime::Lxc => "LXC",
+            ContainerRuntime::Kubernetes => "Kubernetes",
+            ContainerRuntime::SystemdNspawn => "systemd-nspawn",
+            ContainerRuntime::Wsl => "WSL",
+            ContainerRuntime::Unknown => "unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::fixture_path;
+
+    fn fixture_root(name: &str) -> std::path::PathBuf {
+        fixture_path("virt").join(name)
+    }
+
+    #[test]
+    fn test_bare_metal() {
+        let environment = Environment::from_hints(&DetectionHints {
+            dmi: vec!["Dell Inc.".to_string(), "PowerEdge R650".to_string()],
+            init_cgroup: "0::/init.scope\n".to_string(),
+            kernel_release: "6.1.0-18-amd64".to_string(),
+            ..DetectionHints::default()
+        });
+        assert!(environment.is_bare_metal());
+        assert_eq!(environment.describe(), "Bare metal");
+    }
+
+    #[test]
+    fn test_cpuid_vendor() {
+        let kvm = DetectionHints {
+            cpuid_vendor: Some("KVMKVMKVM".to_string()),
+            dmi: vec!["Amazon EC2".to_string()],
+            ..DetectionHints::default()
+        };
+        assert_eq!(detect_hypervisor(&kvm), Some(Hypervisor::Kvm));
+
+        let firecracker = DetectionHints {
+            dmi: Vec::new(),
+            ..kvm
+        };
+        assert_eq!(
+            detect_hypervisor(&firecracker),
+            Some(Hypervisor::Firecracker)
+        );
+
+        let hyperv = DetectionHints {
+            cpuid_vendor: Some("Microsoft Hv".to_string()),
+            ..DetectionHints::default()
+        };
+        assert_eq!(detect_hypervisor(&hyperv), Some(Hypervisor::HyperV));
+    }
+
+    #[test]
+    fn test_dmi_and_fallbacks() {
+        let dmi = |values: &[&str]| DetectionHints {
+            dmi: values.iter().map(|v| v.to_string()).collect(),
+            ..DetectionHints::default()
+        };
+        assert_eq!(
+            detect_hypervisor(&dmi(&["VMware, Inc.", "VMware7,1"])),
+            Some(Hypervisor::Vmware)
+        );
+        assert_eq!(
+            detect_hypervisor(&dmi(&["Microsoft Corporation", "Virtual Machine"])),
+            Some(Hypervisor::HyperV)
+        );
+        assert_eq!(
+            detect_hypervisor(&dmi(&["Microsoft Corporation", "Surface Laptop"])),
+            None
+        );
+        assert_eq!(
+            detect_hypervisor(&DetectionHints {
+                hypervisor_type: Some("xen".to_string()),
+                ..DetectionHints::default()
+            }),
+            Some(Hypervisor::Xen)
+        );
+        assert_eq!(
+            detect_hypervisor(&DetectionHints {
+                hypervisor_flag: true,
+                ..DetectionHints::default()
+            }),
+            Some(Hypervisor::Unknown)
+        );
+    }
+
+    #[test]
+    fn test_container_markers() {
+        let detect = |hints: DetectionHints| detect_container(&hints);
+
+        assert_eq!(
+            detect(DetectionHints {
+                kubernetes_var: true,
+                dockerenv: true,
+                ..DetectionHints::default()
+            }),
+            Some(ContainerRuntime::Kubernetes)
+        );
+        assert_eq!(
+            detect(DetectionHints {
+                container_var: Some("lxc".to_string()),
+                ..DetectionHints::default()
+            }),
+            Some(ContainerRuntime::Lxc)
+        );
+        assert_eq!(
+            detect(DetectionHints {
+                containerenv: true,
+                ..DetectionHints::default()
+            }),
+            Some(ContainerRuntime::Podman)
+        );
+        assert_eq!(
+            detect(DetectionHints {
+                init_cgroup: "12:memory:/docker/3f2a\n".to_string(),
+                ..DetectionHints::default()
+            }),
+            Some(ContainerRuntime::Docker)
+        );
+        assert_eq!(
+            detect(DetectionHints {
+                kernel_release: "5.15.146.1-microsoft-standard-WSL2".to_string(),
+                ..DetectionHints::default()
+            }),
+            Some(ContainerRuntime::Wsl)
+        );
+        assert_eq!(detect(DetectionHints::default()), None);
+    }
+
+    #[test]
+    fn test_read_fixture_hints() {
+        let hints = DetectionHints::read(&fixture_root("qemu_docker"));
+        assert_eq!(
+            hints.dmi,
+            vec!["QEMU", "Standard PC (Q35 + ICH9, 2009)", "SeaBIOS"]
+        );
+        assert!(hints.hypervisor_flag);
+        assert!(hints.dockerenv);
+        assert!(!hints.containerenv);
+
+        let environment = Environment::from_hints(&hints);
+        assert_eq!(environment.hypervisor, Some(Hypervisor::Qemu));
+        assert_eq!(environment.container, Some(ContainerRuntime::Docker));
+        assert_eq!(
+            environment.describe(),
+            "Docker container in a QEMU virtual machine"
+        );
+    }
+}
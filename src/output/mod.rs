@@ -0,0 +1,258 @@
+use crate::models::{
+    BlockDevice, CgroupLimits, CoreInfo, CpuFeatures, CpuTimes, CpuTopology, DiskInfo, DiskIoStats,
+    Environment, HugePages, LoadAverage, MdArray, MemInfo, NetworkDetails, NetworkInfo, Pressure,
+    ProbeStatus, SwapDevice, SystemInfo, ZramDevice, ZswapStatus,
+};
+use crate::Result;
+use serde::{Deserialize, Serialize};
+
+/// Output format for CLI reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Decorated, human-readable text
+    #[default]
+    Text,
+    /// JSON document
+    Json,
+    /// YAML document
+    Yaml,
+    /// TOML document
+    Toml,
+}
+
+impl OutputFormat {
+    /// Serialize a report into this format
+    ///
+    /// Returns an error for [`OutputFormat::Text`], which is rendered by the
+    /// CLI printers rather than by serde.
+    pub fn render<T: Serialize>(self, report: &T) -> Result<String> {
+        let output = match self {
+            OutputFormat::Json => serde_json::to_string_pretty(report)?,
+            OutputFormat::Yaml => serde_yaml::to_string(report)?,
+            OutputFormat::Toml => toml::to_string_pretty(report)?,
+            OutputFormat::Text => anyhow::bail!("text output is not a structured format"),
+        };
+        Ok(output)
+    }
+}
+
+/// Structured document for the `overview` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverviewReport {
+    pub hostname: String,
+    pub os_name: String,
+    pub os_version: String,
+    pub kernel_version: String,
+    pub uptime: u64,
+    pub environment: Environment,
+    pub cpu_brand: String,
+    pub cpu_count: usize,
+    pub cpu_usage: f32,
+    pub load_average: LoadAverage,
+    pub total_memory: u64,
+    pub used_memory: u64,
+    pub disk_count: usize,
+    pub network_count: usize,
+    pub local_ip: Option<String>,
+    pub public_ip: Option<String>,
+    pub public_ip_status: ProbeStatus,
+    pub processes_count: usize,
+    pub pressure: Pressure,
+    pub cgroup: Option<CgroupLimits>,
+}
+
+/// Structured document for the `cpu` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuReport {
+    pub cpu_brand: String,
+    pub cpu_count: usize,
+    pub global_usage: f32,
+    pub load_average: LoadAverage,
+    pub times: Option<CpuTimes>,
+    pub topology: Option<CpuTopology>,
+    pub cores: Vec<CoreInfo>,
+}
+
+/// Structured document for the `cpu --features` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuFeaturesReport {
+    pub cpu_brand: String,
+    pub features: Option<CpuFeatures>,
+}
+
+/// Structured document for the `memory` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryReport {
+    pub total_memory: u64,
+    pub used_memory: u64,
+    pub total_swap: u64,
+    pub used_swap: u64,
+    pub breakdown: Option<MemInfo>,
+    pub swap_devices: Vec<SwapDevice>,
+    pub zram: Vec<ZramDevice>,
+    pub zswap: Option<ZswapStatus>,
+    pub huge_pages: Option<HugePages>,
+}
+
+/// Structured document for the `disks` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisksReport {
+    pub disks: Vec<DiskInfo>,
+    #[serde(default)]
+    pub disk_io: Vec<DiskIoStats>,
+    #[serde(default)]
+    pub block_devices: Vec<BlockDevice>,
+    #[serde(default)]
+    pub raid_arrays: Vec<MdArray>,
+}
+
+/// Structured document for the `network` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkReport {
+    pub network_details: NetworkDetails,
+    pub networks: Vec<NetworkInfo>,
+}
+
+impl From<&SystemInfo> for OverviewReport {
+    fn from(info: &SystemInfo) -> Self {
+        Self {
+            hostname: info.hostname.clone(),
+            os_name: info.os_name.clone(),
+            os_version: info.os_version.clone(),
+            kernel_version: info.kernel_version.clone(),
+            uptime: info.uptime,
+            environment: info.environment,
+            cpu_brand: info.cpu_brand.clone(),
+            cpu_count: info.cpu_count,
+            cpu_usage: info.cpu.global_usage,
+            load_average: info.cpu.load_average,
+            total_memory: info.total_memory,
+            used_memory: info.used_memory,
+            disk_count: info.disks.len(),
+            network_count: info.networks.len(),
+            local_ip: info.network_details.local_ip.clone(),
+            public_ip: info.network_details.public_ip.clone(),
+            public_ip_status: info.network_details.public_ip_status,
+            processes_count: info.processes_count,
+            pressure: info.pressure.clone(),
+            cgroup: info.cgroup.clone(),
+        }
+    }
+}
+
+impl From<&SystemInfo> for CpuReport {
+    fn from(info: &SystemInfo) -> Self {
+        Self {
+            cpu_brand: info.cpu_brand.clone(),
+            cpu_count: info.cpu_count,
+            global_usage: info.cpu.global_usage,
+            load_average: info.cpu.load_average,
+            times: info.cpu.times,
+            topology: info.cpu.topology.clone(),
+            cores: info.cpu.cores.clone(),
+        }
+    }
+}
+
+impl From<&SystemInfo> for CpuFeaturesReport {
+    fn from(info: &SystemInfo) -> Self {
+        Self {
+            cpu_brand: info.cpu_brand.clone(),
+            features: info.cpu.features.clone(),
+        }
+    }
+}
+
+impl From<&SystemInfo> for MemoryReport {
+    fn from(info: &SystemInfo) -> Self {
+        Self {
+            total_memory: info.total_memory,
+            used_memory: info.used_memory,
+            total_swap: info.total_swap,
+            used_swap: info.used_swap,
+            breakdown: info.memory.meminfo,
+            swap_devices: info.memory.swap_devices.clone(),
+            zram: info.memory.zram.clone(),
+            zswap: info.memory.zswap.clone(),
+            huge_pages: info.memory.huge_pages.clone(),
+        }
+    }
+}
+
+impl From<&SystemInfo> for DisksReport {
+    fn from(info: &SystemInfo) -> Self {
+        Self {
+            disks: info.disks.clone(),
+            disk_io: info.disk_io.clone(),
+            block_devices: info.block_devices.clone(),
+            raid_arrays: info.raid_arrays.clone(),
+        }
+    }
+}
+
+impl From<&SystemInfo> for NetworkReport {
+    fn from(info: &SystemInfo) -> Self {
+        Self {
+            network_details: info.network_details.clone(),
+            networks: info.networks.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::InodeUsage;
+
+    fn sample_disks() -> DisksReport {
+        DisksReport {
+            disks: vec![DiskInfo {
+                name: "/dev/sda1".to_string(),
+                mount_point: "/".to_string(),
+                total_space: 1000,
+                available_space: 400,
+                file_system: "ext4".to_string(),
+                inodes: InodeUsage::from_counts(100, 25),
+                dev: Some("8:1".to_string()),
+                source: Some("/dev/sda1".to_string()),
+                mount_options: vec!["rw".to_string(), "relatime".to_string()],
+                forecast: None,
+            }],
+            disk_io: vec![DiskIoStats {
+                device: "sda".to_string(),
+                read_bytes_per_sec: 2048.0,
+                utilization_percent: 12.5,
+                interval_ms: 1000,
+                ..DiskIoStats::default()
+            }],
+            block_devices: Vec::new(),
+            raid_arrays: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_render_json() {
+        let output = OutputFormat::Json.render(&sample_disks()).unwrap();
+        let parsed: DisksReport = serde_json::from_str(&output).unwrap();
+        assert_eq!(parsed.disks[0].mount_point, "/");
+    }
+
+    #[test]
+    fn test_render_yaml() {
+        let output = OutputFormat::Yaml.render(&sample_disks()).unwrap();
+        let parsed: DisksReport = serde_yaml::from_str(&output).unwrap();
+        assert_eq!(parsed.disks[0].total_space, 1000);
+    }
+
+    #[test]
+    fn test_render_toml() {
+        let output = OutputFormat::Toml.render(&sample_disks()).unwrap();
+        let parsed: DisksReport = toml::from_str(&output).unwrap();
+        assert_eq!(parsed.disks[0].file_system, "ext4");
+    }
+
+    #[test]
+    fn test_render_text_is_error() {
+        assert!(OutputFormat::Text.render(&sample_disks()).is_err());
+    }
+}
@@ -0,0 +1,247 @@
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, UdpSocket};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const HEADER_LEN: usize = 12;
+const CLASS_IN: u16 = 1;
+/// Flags for a standard query with recursion desired
+const FLAGS_RECURSION_DESIRED: u16 = 0x0100;
+/// Most labels a valid name can have (255 bytes of one-character labels)
+const MAX_NAME_LABELS: usize = 128;
+
+/// DNS record type answering a "what is my IP" query
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DnsRecordType {
+    /// IPv4 address record (OpenDNS `myip.opendns.com`)
+    #[default]
+    A,
+    /// IPv6 address record
+    Aaaa,
+    /// Text record holding the address (Google `o-o.myaddr.l.google.com`)
+    Txt,
+}
+
+impl DnsRecordType {
+    fn code(self) -> u16 {
+        match self {
+            DnsRecordType::A => 1,
+            DnsRecordType::Aaaa => 28,
+            DnsRecordType::Txt => 16,
+        }
+    }
+}
+
+/// Send `name` to the DNS server at `server` and return the address it answers
+///
+/// Looking up the server's host name and waiting for the answer are each
+/// limited by `timeout`.
+pub fn query(server: &str, name: &str, record: DnsRecordType, timeout: Duration) -> Result<IpAddr> {
+    let server_addr = super::resolve_server(server, timeout)?;
+    let bind_addr = if server_addr.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+
+    let socket = UdpSocket::bind(bind_addr)?;
+    socket.set_read_timeout(Some(timeout))?;
+    socket.connect(server_addr)?;
+
+    let id = new_query_id();
+    socket.send(&build_query(id, name, record)?)?;
+
+    let mut buf = [0u8; 1232];
+    let len = socket.recv(&mut buf)?;
+    parse_response(&buf[..len], id, record)
+}
+
+/// Build a single-question query packet
+pub fn build_query(id: u16, name: &str, record: DnsRecordType) -> Result<Vec<u8>> {
+    let mut packet = Vec::with_capacity(HEADER_LEN + name.len() + 6);
+    packet.extend_from_slice(&id.to_be_bytes());
+    packet.extend_from_slice(&FLAGS_RECURSION_DESIRED.to_be_bytes());
+    packet.extend_from_slice(&1u16.to_be_bytes()); // QDCOUNT
+    packet.extend_from_slice(&[0; 6]); // ANCOUNT, NSCOUNT, ARCOUNT
+
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            anyhow::bail!("invalid DNS name '{}'", name);
+        }
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+
+    packet.extend_from_slice(&record.code().to_be_bytes());
+    packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Ok(packet)
+}
+
+/// Extract the first address of the requested type from a response
+pub fn parse_response(packet: &[u8], id: u16, record: DnsRecordType) -> Result<IpAddr> {
+    if packet.len() < HEADER_LEN {
+        anyhow::bail!("DNS response too short");
+    }
+    if u16::from_be_bytes([packet[0], packet[1]]) != id {
+        anyhow::bail!("DNS response does not match the query");
+    }
+    let rcode = packet[3] & 0x0f;
+    if rcode != 0 {
+        anyhow::bail!("DNS server returned error code {}", rcode);
+    }
+    let questions = u16::from_be_bytes([packet[4], packet[5]]);
+    let answers = u16::from_be_bytes([packet[6], packet[7]]);
+
+    let mut offset = HEADER_LEN;
+    for _ in 0..questions {
+        offset = skip_name(packet, offset)? + 4; // QTYPE, QCLASS
+    }
+
+    for _ in 0..answers {
+        offset = skip_name(packet, offset)?;
+        let fixed = packet
+            .get(offset..offset + 10)
+            .ok_or_else(|| anyhow::anyhow!("DNS answer truncated"))?;
+        let record_type = u16::from_be_bytes([fixed[0], fixed[1]]);
+        let rdlength = u16::from_be_bytes([fixed[8], fixed[9]]) as usize;
+        offset += 10;
+        let rdata = packet
+            .get(offset..offset + rdlength)
+            .ok_or_else(|| anyhow::anyhow!("DNS answer truncated"))?;
+        offset += rdlength;
+
+        if record_type != record.code() {
+            continue;
+        }
+        match record {
+            DnsRecordType::A => {
+                if let Ok(octets) = <[u8; 4]>::try_from(rdata) {
+                    return Ok(IpAddr::V4(Ipv4Addr::from(octets)));
+                }
+            }
+            DnsRecordType::Aaaa => {
+                if let Ok(octets) = <[u8; 16]>::try_from(rdata) {
+                    return Ok(IpAddr::V6(Ipv6Addr::from(octets)));
+                }
+            }
+            DnsRecordType::Txt => {
+                // TXT data is a sequence of length-prefixed strings
+                let mut text_offset = 0;
+                while let Some(&len) = rdata.get(text_offset) {
+                    let text = rdata
+                        .get(text_offset + 1..text_offset + 1 + len as usize)
+                        .ok_or_else(|| anyhow::anyhow!("DNS TXT record truncated"))?;
+                    if let Ok(ip) = String::from_utf8_lossy(text).trim().parse::<IpAddr>() {
+                        return Ok(ip);
+                    }
+                    text_offset += 1 + len as usize;
+                }
+            }
+        }
+    }
+
+    anyhow::bail!("DNS response has no usable {:?} record", record)
+}
+
+/// Return the offset just past the (possibly compressed) name at `offset`
+fn skip_name(packet: &[u8], mut offset: usize) -> Result<usize> {
+    for _ in 0..MAX_NAME_LABELS {
+        let len = *packet
+            .get(offset)
+            .ok_or_else(|| anyhow::anyhow!("DNS name truncated"))?;
+        match len {
+            0 => return Ok(offset + 1),
+            // A compression pointer ends the name in this position
+            len if len & 0xc0 == 0xc0 => return Ok(offset + 2),
+            len => offset += 1 + len as usize,
+        }
+    }
+    anyhow::bail!("DNS name too long")
+}
+
+fn new_query_id() -> u16 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    (nanos ^ std::process::id()) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a response to `query` carrying one answer with `rdata`
+    fn response_to(query: &[u8], record: DnsRecordType, rdata: &[u8]) -> Vec<u8> {
+        let mut packet = query.to_vec();
+        packet[2] = 0x81; // QR, RD
+        packet[3] = 0x80; // RA, NOERROR
+        packet[7] = 1; // ANCOUNT
+        packet.extend_from_slice(&[0xc0, 0x0c]); // pointer to the question name
+        packet.extend_from_slice(&record.code().to_be_bytes());
+        packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+        packet.extend_from_slice(&60u32.to_be_bytes());
+        packet.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+        packet.extend_from_slice(rdata);
+        packet
+    }
+
+    #[test]
+    fn test_build_query() {
+        let packet = build_query(0x1234, "myip.opendns.com", DnsRecordType::A).unwrap();
+        assert_eq!(&packet[0..2], &[0x12, 0x34]);
+        assert_eq!(&packet[4..6], &[0, 1]);
+        assert_eq!(&packet[12..17], b"\x04myip");
+        assert_eq!(&packet[packet.len() - 4..], &[0, 1, 0, 1]);
+        assert!(build_query(1, "bad..name", DnsRecordType::A).is_err());
+    }
+
+    #[test]
+    fn test_parse_a_record() {
+        let query = build_query(7, "myip.opendns.com", DnsRecordType::A).unwrap();
+        let packet = response_to(&query, DnsRecordType::A, &[203, 0, 113, 5]);
+        let ip = parse_response(&packet, 7, DnsRecordType::A).unwrap();
+        assert_eq!(ip, "203.0.113.5".parse::<IpAddr>().unwrap());
+    }
+
+    #[test]
+    fn test_parse_txt_record() {
+        let query = build_query(9, "o-o.myaddr.l.google.com", DnsRecordType::Txt).unwrap();
+        let packet = response_to(&query, DnsRecordType::Txt, b"\x0b2001:db8::7");
+        let ip = parse_response(&packet, 9, DnsRecordType::Txt).unwrap();
+        assert_eq!(ip, "2001:db8::7".parse::<IpAddr>().unwrap());
+    }
+
+    #[test]
+    fn test_parse_rejects_errors() {
+        let query = build_query(3, "myip.opendns.com", DnsRecordType::A).unwrap();
+        let mut packet = response_to(&query, DnsRecordType::A, &[203, 0, 113, 5]);
+        assert!(parse_response(&packet, 4, DnsRecordType::A).is_err());
+        assert!(parse_response(&packet, 3, DnsRecordType::Aaaa).is_err());
+        packet[3] = 0x83; // NXDOMAIN
+        assert!(parse_response(&packet, 3, DnsRecordType::A).is_err());
+    }
+
+    #[test]
+    fn test_query_against_stand_in_server() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 512];
+            let (len, peer) = server.recv_from(&mut buf).unwrap();
+            let packet = response_to(&buf[..len], DnsRecordType::A, &[198, 51, 100, 3]);
+            server.send_to(&packet, peer).unwrap();
+        });
+
+        let ip = query(
+            &addr.to_string(),
+            "myip.opendns.com",
+            DnsRecordType::A,
+            Duration::from_secs(2),
+        )
+        .unwrap();
+        assert_eq!(ip, "198.51.100.3".parse::<IpAddr>().unwrap());
+    }
+}
@@ -0,0 +1,314 @@
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+pub mod dns;
+pub mod stun;
+
+pub use dns::DnsRecordType;
+
+/// Services queried for the public IP when none are configured
+const DEFAULT_PUBLIC_IP_URLS: &[&str] = &[
+    "https://api.ipify.org",
+    "https://ifconfig.me/ip",
+    "https://icanhazip.com",
+];
+
+/// Downloads used for the bandwidth benchmark when none are configured
+const DEFAULT_BANDWIDTH_URLS: &[&str] = &[
+    "https://speed.cloudflare.com/__down?bytes=1000000", // ~976 KB test
+];
+
+/// A way of discovering this host's public IP address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PublicIpResolver {
+    /// HTTP(S) echo service returning the caller's address
+    Http {
+        url: String,
+        #[serde(default)]
+        parser: ResponseParser,
+    },
+    /// STUN binding request, e.g. `stun.l.google.com:19302`
+    Stun { server: String },
+    /// DNS query answered with the caller's address, e.g. OpenDNS
+    /// `myip.opendns.com` (A) against `resolver1.opendns.com:53`
+    Dns {
+        server: String,
+        query: String,
+        #[serde(default)]
+        record: DnsRecordType,
+    },
+}
+
+/// How to extract the address from an HTTP response body
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "format", rename_all = "snake_case")]
+pub enum ResponseParser {
+    /// The body is the address, optionally surrounded by whitespace
+    #[default]
+    PlainText,
+    /// The body is JSON and the address is at a dotted field path such as
+    /// `data.client.ip` (array elements are addressed by index)
+    Json { field: String },
+}
+
+impl ResponseParser {
+    /// Extract an IP address from a response body
+    pub fn parse(&self, body: &str) -> Result<IpAddr> {
+        let text = match self {
+            ResponseParser::PlainText => body.trim().to_string(),
+            ResponseParser::Json { field } => {
+                let mut value: &serde_json::Value = &serde_json::from_str(body)?;
+                for segment in field.split('.') {
+                    value = match segment.parse::<usize>() {
+                        Ok(index) if value.is_array() => &value[index],
+                        _ => &value[segment],
+                    };
+                }
+                value
+                    .as_str()
+                    .ok_or_else(|| anyhow::anyhow!("field '{}' is not a string", field))?
+                    .trim()
+                    .to_string()
+            }
+        };
+        text.parse::<IpAddr>()
+            .map_err(|_| anyhow::anyhow!("'{}' is not an IP address", text))
+    }
+}
+
+impl PublicIpResolver {
+    /// Query this resolver, giving up after `timeout`
+    pub fn resolve(&self, timeout: Duration) -> Result<IpAddr> {
+        match self {
+            PublicIpResolver::Http { url, parser } => {
+                let body = reqwest::blocking::Client::builder()
+                    .timeout(timeout)
+                    .build()?
+                    .get(url)
+                    .send()?
+                    .error_for_status()?
+                    .text()?;
+                parser.parse(&body)
+            }
+            PublicIpResolver::Stun { server } => stun::query(server, timeout),
+            PublicIpResolver::Dns {
+                server,
+                query,
+                record,
+            } => dns::query(server, query, *record, timeout),
+        }
+    }
+}
+
+/// Resolvers used when none are configured
+pub fn default_public_ip_resolvers() -> Vec<PublicIpResolver> {
+    DEFAULT_PUBLIC_IP_URLS
+        .iter()
+        .map(|url| PublicIpResolver::Http {
+            url: url.to_string(),
+            parser: ResponseParser::PlainText,
+        })
+        .collect()
+}
+
+/// Bandwidth benchmark downloads used when none are configured
+pub fn default_bandwidth_urls() -> Vec<String> {
+    DEFAULT_BANDWIDTH_URLS
+        .iter()
+        .map(|url| url.to_string())
+        .collect()
+}
+
+/// Try each resolver in order and return the first address found
+pub fn resolve_public_ip(resolvers: &[PublicIpResolver], timeout: Duration) -> Option<IpAddr> {
+    resolvers
+        .iter()
+        .find_map(|resolver| resolver.resolve(timeout).ok())
+}
+
+/// Download from each URL in order and return the first measured rate in Mbps
+pub fn benchmark_bandwidth(urls: &[String], timeout: Duration) -> Option<f64> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(timeout)
+        .build()
+        .ok()?;
+
+    for url in urls {
+        let start = Instant::now();
+
+        // Error pages would otherwise be timed as successful downloads
+        let Ok(bytes) = client
+            .get(url)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.bytes())
+        else {
+            continue;
+        };
+
+        let duration_secs = start.elapsed().as_secs_f64();
+        if duration_secs > 0.0 {
+            let bytes_count = bytes.len() as f64;
+            // Calculate Mbps (megabits per second)
+            return Some((bytes_count * 8.0) / (duration_secs * 1_000_000.0));
+        }
+    }
+    None
+}
+
+/// Resolve a `host:port` UDP server address, giving up after `timeout`
+///
+/// The system resolver cannot be interrupted, so the lookup runs on its own
+/// thread, which is left to finish in the background on timeout.
+fn resolve_server(server: &str, timeout: Duration) -> Result<SocketAddr> {
+    let (tx, rx) = mpsc::channel();
+    let host = server.to_string();
+    std::thread::spawn(move || {
+        let _ = tx.send(host.to_socket_addrs().map(|mut addrs| addrs.next()));
+    });
+    match rx.recv_timeout(timeout) {
+        Ok(Ok(Some(addr))) => Ok(addr),
+        Ok(Ok(None)) => anyhow::bail!("{} has no addresses", server),
+        Ok(Err(err)) => Err(anyhow::anyhow!("could not resolve {}: {}", server, err)),
+        Err(_) => anyhow::bail!("timed out resolving {}", server),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serve `body` once per request on a local port, returning its base URL
+    fn stand_in_server(body: &'static str) -> String {
+        stand_in_server_with_status(200, body)
+    }
+
+    fn stand_in_server_with_status(status: u16, body: &'static str) -> String {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let addr = server.server_addr().to_ip().unwrap();
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let response = tiny_http::Response::from_string(body).with_status_code(status);
+                let _ = request.respond(response);
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_parse_plain_text() {
+        let ip = ResponseParser::PlainText.parse(" 203.0.113.7\n").unwrap();
+        assert_eq!(ip, "203.0.113.7".parse::<IpAddr>().unwrap());
+        assert!(ResponseParser::PlainText.parse("<html>").is_err());
+    }
+
+    #[test]
+    fn test_parse_json_field_path() {
+        let parser = ResponseParser::Json {
+            field: "data.addresses.1".to_string(),
+        };
+        let body = r#"{"data": {"addresses": ["10.0.0.1", "2001:db8::1"]}}"#;
+        assert_eq!(
+            parser.parse(body).unwrap(),
+            "2001:db8::1".parse::<IpAddr>().unwrap()
+        );
+
+        let missing = ResponseParser::Json {
+            field: "data.ip".to_string(),
+        };
+        assert!(missing.parse(body).is_err());
+    }
+
+    #[test]
+    fn test_resolver_config_format() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            resolvers: Vec<PublicIpResolver>,
+        }
+
+        let wrapper: Wrapper = toml::from_str(
+            r#"
+            resolvers = [
+                { type = "http", url = "http://echo.internal/ip" },
+                { type = "http", url = "http://echo.internal/json", parser = { format = "json", field = "ip" } },
+                { type = "stun", server = "stun.internal:3478" },
+                { type = "dns", server = "10.0.0.53:53", query = "myip.internal", record = "txt" },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(wrapper.resolvers.len(), 4);
+        assert_eq!(
+            wrapper.resolvers[1],
+            PublicIpResolver::Http {
+                url: "http://echo.internal/json".to_string(),
+                parser: ResponseParser::Json {
+                    field: "ip".to_string()
+                },
+            }
+        );
+        assert_eq!(
+            wrapper.resolvers[3],
+            PublicIpResolver::Dns {
+                server: "10.0.0.53:53".to_string(),
+                query: "myip.internal".to_string(),
+                record: DnsRecordType::Txt,
+            }
+        );
+    }
+
+    #[test]
+    fn test_resolve_against_stand_in_server() {
+        let plain = stand_in_server("198.51.100.20\n");
+        let json = stand_in_server(r#"{"client": {"ip": "198.51.100.21"}}"#);
+
+        let resolvers = vec![
+            PublicIpResolver::Http {
+                url: "http://127.0.0.1:1/unreachable".to_string(),
+                parser: ResponseParser::PlainText,
+            },
+            PublicIpResolver::Http {
+                url: json,
+                parser: ResponseParser::Json {
+                    field: "client.ip".to_string(),
+                },
+            },
+            PublicIpResolver::Http {
+                url: plain,
+                parser: ResponseParser::PlainText,
+            },
+        ];
+
+        let ip = resolve_public_ip(&resolvers, Duration::from_secs(2));
+        assert_eq!(ip, Some("198.51.100.21".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_benchmark_against_stand_in_server() {
+        let url = stand_in_server("0123456789abcdef");
+        let mbps = benchmark_bandwidth(std::slice::from_ref(&url), Duration::from_secs(2));
+        assert!(mbps.is_some_and(|mbps| mbps > 0.0));
+
+        // Error responses move on to the next URL
+        let missing = stand_in_server_with_status(404, "not found");
+        assert!(
+            benchmark_bandwidth(std::slice::from_ref(&missing), Duration::from_secs(2)).is_none()
+        );
+        assert!(benchmark_bandwidth(&[missing, url], Duration::from_secs(2)).is_some());
+    }
+
+    #[test]
+    fn test_resolve_server() {
+        let timeout = Duration::from_secs(2);
+        assert_eq!(
+            resolve_server("127.0.0.1:3478", timeout).unwrap(),
+            "127.0.0.1:3478".parse().unwrap()
+        );
+        assert!(resolve_server("no port", timeout).is_err());
+    }
+}
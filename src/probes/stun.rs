@@ -0,0 +1,216 @@
+use crate::Result;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, UdpSocket};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// STUN magic cookie (RFC 5389)
+const MAGIC_COOKIE: u32 = 0x2112_A442;
+
+const BINDING_REQUEST: u16 = 0x0001;
+const BINDING_SUCCESS: u16 = 0x0101;
+
+const ATTR_MAPPED_ADDRESS: u16 = 0x0001;
+const ATTR_XOR_MAPPED_ADDRESS: u16 = 0x0020;
+
+const HEADER_LEN: usize = 20;
+
+/// Send a STUN binding request to `server` and return the reflexive address
+///
+/// `timeout` limits the server lookup and the wait for the response alike.
+pub fn query(server: &str, timeout: Duration) -> Result<IpAddr> {
+    let server_addr = super::resolve_server(server, timeout)?;
+    let bind_addr = if server_addr.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+
+    let socket = UdpSocket::bind(bind_addr)?;
+    socket.set_read_timeout(Some(timeout))?;
+    socket.connect(server_addr)?;
+
+    let transaction_id = new_transaction_id();
+    socket.send(&binding_request(&transaction_id))?;
+
+    let mut buf = [0u8; 512];
+    let len = socket.recv(&mut buf)?;
+    parse_binding_response(&buf[..len], &transaction_id)
+}
+
+/// Build a binding request with no attributes
+pub fn binding_request(transaction_id: &[u8; 12]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(HEADER_LEN);
+    packet.extend_from_slice(&BINDING_REQUEST.to_be_bytes());
+    packet.extend_from_slice(&0u16.to_be_bytes());
+    packet.extend_from_slice(&MAGIC_COOKIE.to_be_bytes());
+    packet.extend_from_slice(transaction_id);
+    packet
+}
+
+/// Extract the mapped address from a binding success response
+pub fn parse_binding_response(packet: &[u8], transaction_id: &[u8; 12]) -> Result<IpAddr> {
+    if packet.len() < HEADER_LEN {
+        anyhow::bail!("STUN response too short");
+    }
+    let message_type = u16::from_be_bytes([packet[0], packet[1]]);
+    let length = u16::from_be_bytes([packet[2], packet[3]]) as usize;
+    if message_type != BINDING_SUCCESS {
+        anyhow::bail!("unexpected STUN message type {:#06x}", message_type);
+    }
+    if packet[4..8] != MAGIC_COOKIE.to_be_bytes() || packet[8..20] != transaction_id[..] {
+        anyhow::bail!("STUN response does not match the request");
+    }
+
+    let attributes = packet
+        .get(HEADER_LEN..HEADER_LEN + length)
+        .ok_or_else(|| anyhow::anyhow!("STUN response truncated"))?;
+
+    let mut mapped = None;
+    let mut offset = 0;
+    while offset + 4 <= attributes.len() {
+        let attr_type = u16::from_be_bytes([attributes[offset], attributes[offset + 1]]);
+        let attr_len =
+            u16::from_be_bytes([attributes[offset + 2], attributes[offset + 3]]) as usize;
+        let value = attributes
+            .get(offset + 4..offset + 4 + attr_len)
+            .ok_or_else(|| anyhow::anyhow!("STUN attribute truncated"))?;
+
+        match attr_type {
+            // XOR-MAPPED-ADDRESS is preferred since NATs may rewrite MAPPED-ADDRESS
+            ATTR_XOR_MAPPED_ADDRESS => return decode_address(value, Some(transaction_id)),
+            ATTR_MAPPED_ADDRESS => mapped = Some(decode_address(value, None)?),
+            _ => {}
+        }
+
+        // Attributes are padded to a multiple of four bytes
+        offset += 4 + attr_len.div_ceil(4) * 4;
+    }
+
+    mapped.ok_or_else(|| anyhow::anyhow!("STUN response has no mapped address"))
+}
+
+/// Decode a (XOR-)MAPPED-ADDRESS value; `xor_key` is set for the XOR variant
+fn decode_address(value: &[u8], xor_key: Option<&[u8; 12]>) -> Result<IpAddr> {
+    let family = *value
+        .get(1)
+        .ok_or_else(|| anyhow::anyhow!("STUN address truncated"))?;
+    let cookie = MAGIC_COOKIE.to_be_bytes();
+
+    match family {
+        0x01 => {
+            let mut octets: [u8; 4] = value
+                .get(4..8)
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| anyhow::anyhow!("STUN IPv4 address truncated"))?;
+            if xor_key.is_some() {
+                for (octet, key) in octets.iter_mut().zip(cookie) {
+                    *octet ^= key;
+                }
+            }
+            Ok(IpAddr::V4(Ipv4Addr::from(octets)))
+        }
+        0x02 => {
+            let mut octets: [u8; 16] = value
+                .get(4..20)
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| anyhow::anyhow!("STUN IPv6 address truncated"))?;
+            if let Some(transaction_id) = xor_key {
+                let key = cookie.iter().chain(transaction_id.iter());
+                for (octet, key) in octets.iter_mut().zip(key) {
+                    *octet ^= key;
+                }
+            }
+            Ok(IpAddr::V6(Ipv6Addr::from(octets)))
+        }
+        other => anyhow::bail!("unknown STUN address family {}", other),
+    }
+}
+
+/// A transaction ID unique enough to match our own response
+fn new_transaction_id() -> [u8; 12] {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let mut id = [0u8; 12];
+    id[..8].copy_from_slice(&(nanos as u64).to_be_bytes());
+    id[8..].copy_from_slice(&std::process::id().to_be_bytes());
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSACTION_ID: [u8; 12] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+
+    fn response(attributes: &[u8]) -> Vec<u8> {
+        let mut packet = Vec::new();
+        packet.extend_from_slice(&BINDING_SUCCESS.to_be_bytes());
+        packet.extend_from_slice(&(attributes.len() as u16).to_be_bytes());
+        packet.extend_from_slice(&MAGIC_COOKIE.to_be_bytes());
+        packet.extend_from_slice(&TRANSACTION_ID);
+        packet.extend_from_slice(attributes);
+        packet
+    }
+
+    /// XOR-MAPPED-ADDRESS attribute for 192.0.2.1:32853 (RFC 5769 sample)
+    fn xor_mapped_ipv4() -> Vec<u8> {
+        vec![
+            0x00, 0x20, 0x00, 0x08, 0x00, 0x01, 0xa1, 0x47, 0xe1, 0x12, 0xa6, 0x43,
+        ]
+    }
+
+    #[test]
+    fn test_binding_request_layout() {
+        let packet = binding_request(&TRANSACTION_ID);
+        assert_eq!(packet.len(), HEADER_LEN);
+        assert_eq!(&packet[0..4], &[0x00, 0x01, 0x00, 0x00]);
+        assert_eq!(&packet[4..8], &MAGIC_COOKIE.to_be_bytes());
+        assert_eq!(&packet[8..], &TRANSACTION_ID);
+    }
+
+    #[test]
+    fn test_parse_xor_mapped_ipv4() {
+        let ip = parse_binding_response(&response(&xor_mapped_ipv4()), &TRANSACTION_ID).unwrap();
+        assert_eq!(ip, "192.0.2.1".parse::<IpAddr>().unwrap());
+    }
+
+    #[test]
+    fn test_parse_mapped_address_fallback() {
+        // SOFTWARE attribute (odd length, padded) followed by MAPPED-ADDRESS
+        let attributes = [
+            0x80, 0x22, 0x00, 0x03, b'a', b'b', b'c', 0x00, 0x00, 0x01, 0x00, 0x08, 0x00, 0x01,
+            0x1f, 0x90, 203, 0, 113, 9,
+        ];
+        let ip = parse_binding_response(&response(&attributes), &TRANSACTION_ID).unwrap();
+        assert_eq!(ip, "203.0.113.9".parse::<IpAddr>().unwrap());
+    }
+
+    #[test]
+    fn test_rejects_mismatched_transaction() {
+        let other = [0u8; 12];
+        assert!(parse_binding_response(&response(&xor_mapped_ipv4()), &other).is_err());
+        assert!(parse_binding_response(&[0u8; 4], &TRANSACTION_ID).is_err());
+    }
+
+    #[test]
+    fn test_query_against_stand_in_server() {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 512];
+            let (len, peer) = server.recv_from(&mut buf).unwrap();
+            let transaction_id: [u8; 12] = buf[8..len].try_into().unwrap();
+            let mut packet = Vec::new();
+            packet.extend_from_slice(&BINDING_SUCCESS.to_be_bytes());
+            packet.extend_from_slice(&12u16.to_be_bytes());
+            packet.extend_from_slice(&MAGIC_COOKIE.to_be_bytes());
+            packet.extend_from_slice(&transaction_id);
+            packet.extend_from_slice(&xor_mapped_ipv4());
+            server.send_to(&packet, peer).unwrap();
+        });
+
+        let ip = query(&addr.to_string(), Duration::from_secs(2)).unwrap();
+        assert_eq!(ip, "192.0.2.1".parse::<IpAddr>().unwrap());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::models::SystemInfo;
+use crate::models::{BlockDevice, CollectOptions, DiskHistory, DiskIoStats, Section, SystemInfo};
 use crate::Result;
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
@@ -9,40 +9,100 @@ use ratatui::{
     backend::{Backend, CrosstermBackend},
     layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
     Frame, Terminal,
 };
+use std::collections::HashMap;
 use std::io;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
+mod usage;
+mod worker;
+
+use usage::UsageView;
+use worker::{Collector, Update};
+
 /// Width of progress bars in characters
 const PROGRESS_BAR_WIDTH: usize = 50;
 
 /// Application state
 pub struct App {
     system_info: SystemInfo,
-    last_refresh: Instant,
+    collector: Collector,
+    sections: Vec<Section>,
+    /// When each section was last collected
+    section_updated: HashMap<Section, Instant>,
+    /// Sections requested but not yet received since the last refresh
+    pending: Vec<Section>,
     status_message: String,
     current_tab: Tab,
+    /// Disk highlighted in the Disks tab
+    selected_disk: usize,
+    /// Disk usage drill-down started from the Disks tab
+    usage: Option<UsageView>,
+    /// Free space history feeding disk-fill forecasts
+    disk_history: Option<HistoryFile>,
+}
+
+/// Location and window of the disk history file
+///
+/// The file is re-read on every update so samples recorded meanwhile by other
+/// runs, e.g. `check` from cron, are kept.
+struct HistoryFile {
+    path: PathBuf,
+    window: Duration,
+    /// Forecast time until full below which a disk is flagged
+    critical_within: Duration,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Tab {
     Overview,
+    Cpu,
     Memory,
     Disks,
     Network,
+    Usage,
 }
 
 impl App {
     pub fn new() -> Self {
+        Self::with_options(CollectOptions::default())
+    }
+
+    /// Create an app that collects system information with `collect_options`
+    ///
+    /// Collection starts immediately on background threads.
+    pub fn with_options(collect_options: CollectOptions) -> Self {
         Self {
-            system_info: SystemInfo::collect(),
-            last_refresh: Instant::now(),
+            system_info: SystemInfo::default(),
+            collector: Collector::spawn(&collect_options),
+            sections: collect_options.sections.clone(),
+            section_updated: HashMap::new(),
+            pending: collect_options.sections,
             status_message: "Welcome to System Index! Press 'h' for help, 'q' to quit.".to_string(),
             current_tab: Tab::Overview,
+            selected_disk: 0,
+            usage: None,
+            disk_history: None,
         }
     }
+
+    /// Record free space samples to `path` and show disk-fill forecasts
+    pub fn with_disk_history(
+        mut self,
+        path: PathBuf,
+        window: Duration,
+        critical_within: Duration,
+    ) -> Self {
+        self.disk_history = Some(HistoryFile {
+            path,
+            window,
+            critical_within,
+        });
+        self
+    }
 }
 
 impl Default for App {
@@ -76,12 +136,14 @@ impl App {
 
     fn run_app<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
         loop {
-            terminal.draw(|f| self.ui(f))?;
-
-            // Auto-refresh every 2 seconds
-            if self.last_refresh.elapsed() > Duration::from_secs(2) {
-                self.refresh();
+            while let Some(update) = self.collector.try_recv() {
+                self.apply_update(update);
             }
+            if let Some(usage) = &mut self.usage {
+                usage.poll();
+            }
+
+            terminal.draw(|f| self.ui(f))?;
 
             if event::poll(Duration::from_millis(100))? {
                 if let Event::Key(key) = event::read()? {
@@ -96,43 +158,158 @@ impl App {
     }
 
     fn handle_input(&mut self, key: KeyCode) -> Result<bool> {
+        let handled = match self.current_tab {
+            Tab::Disks => self.handle_disks_input(key),
+            Tab::Usage => self.handle_usage_input(key),
+            _ => false,
+        };
+        if handled {
+            return Ok(false);
+        }
+
         match key {
             KeyCode::Char('q') => return Ok(true),
             KeyCode::Char('h') => {
-                self.status_message =
-                    "Keys: q=quit, r=refresh, 1=overview, 2=memory, 3=disks, 4=network".to_string();
+                self.status_message = "Keys: q=quit, r=refresh, 1=overview, 2=cpu, 3=memory, \
+                                       4=disks, 5=network, 6=usage; Disks: ↑/↓ select, \
+                                       Enter analyze usage; Usage: Enter open, ←/Backspace up, \
+                                       Esc back"
+                    .to_string();
             }
             KeyCode::Char('r') => {
                 self.refresh();
-                self.status_message = "System information refreshed!".to_string();
+                self.status_message = "Refreshing system information…".to_string();
             }
             KeyCode::Char('1') => {
                 self.current_tab = Tab::Overview;
                 self.status_message = "Showing: Overview".to_string();
             }
             KeyCode::Char('2') => {
+                self.current_tab = Tab::Cpu;
+                self.status_message = "Showing: CPU".to_string();
+            }
+            KeyCode::Char('3') => {
                 self.current_tab = Tab::Memory;
                 self.status_message = "Showing: Memory".to_string();
             }
-            KeyCode::Char('3') => {
+            KeyCode::Char('4') => {
                 self.current_tab = Tab::Disks;
                 self.status_message = "Showing: Disks".to_string();
             }
-            KeyCode::Char('4') => {
+            KeyCode::Char('5') => {
                 self.current_tab = Tab::Network;
                 self.status_message = "Showing: Network".to_string();
             }
+            KeyCode::Char('6') if self.usage.is_some() => {
+                self.current_tab = Tab::Usage;
+                self.status_message = "Showing: Usage".to_string();
+            }
             _ => {}
         }
         Ok(false)
     }
 
+    /// Disk selection keys; returns whether the key was handled
+    fn handle_disks_input(&mut self, key: KeyCode) -> bool {
+        let disk_count = self.system_info.disks.len();
+        match key {
+            KeyCode::Up => self.selected_disk = self.selected_disk.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected_disk = (self.selected_disk + 1).min(disk_count.saturating_sub(1))
+            }
+            KeyCode::Enter | KeyCode::Char('u') => {
+                let Some(disk) = self.system_info.disks.get(self.selected_disk) else {
+                    return true;
+                };
+                let view = UsageView::start(disk.mount_point.clone().into());
+                self.status_message = format!(
+                    "Analyzing usage of {}: Enter open, ←/Backspace up, Esc back",
+                    view.path().display()
+                );
+                self.usage = Some(view);
+                self.current_tab = Tab::Usage;
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Drill-down keys; returns whether the key was handled
+    fn handle_usage_input(&mut self, key: KeyCode) -> bool {
+        let Some(usage) = &mut self.usage else {
+            return false;
+        };
+        match key {
+            KeyCode::Up => usage.select_previous(),
+            KeyCode::Down => usage.select_next(),
+            KeyCode::Enter | KeyCode::Right => usage.enter(),
+            KeyCode::Backspace | KeyCode::Left => {
+                usage.leave();
+            }
+            KeyCode::Esc => {
+                // Dropping the view cancels a scan still in progress
+                self.usage = None;
+                self.current_tab = Tab::Disks;
+                self.status_message = "Showing: Disks".to_string();
+            }
+            _ => return false,
+        }
+        true
+    }
+
     fn refresh(&mut self) {
-        self.system_info = SystemInfo::collect();
-        self.last_refresh = Instant::now();
+        self.collector.request_refresh();
+        self.pending = self.sections.clone();
+    }
+
+    fn apply_update(&mut self, update: Update) {
+        self.system_info
+            .merge_sections(&update.info, &update.sections);
+        for section in &update.sections {
+            self.section_updated.insert(*section, update.collected_at);
+        }
+        self.pending
+            .retain(|section| !update.sections.contains(section));
+        if update.sections.contains(&Section::Disks) {
+            self.update_forecasts();
+        }
+    }
+
+    fn update_forecasts(&mut self) {
+        let Some(file) = &self.disk_history else {
+            return;
+        };
+        if let Err(err) = DiskHistory::update(&file.path, &mut self.system_info.disks, file.window)
+        {
+            self.status_message = format!("Disk history not updated: {:#}", err);
+        }
+    }
+
+    /// Age of the oldest of `sections`, for block titles
+    fn staleness(&self, sections: &[Section]) -> String {
+        let oldest = sections
+            .iter()
+            .filter(|section| self.sections.contains(section))
+            .map(|section| self.section_updated.get(section))
+            .try_fold(None::<Instant>, |oldest, updated| {
+                updated.map(|updated| Some(oldest.map_or(*updated, |o| o.min(*updated))))
+            });
+
+        match oldest {
+            Some(Some(updated)) => format!(
+                "updated {} ago",
+                SystemInfo::format_uptime(updated.elapsed().as_secs())
+            ),
+            Some(None) => "not collected".to_string(),
+            None => "collecting…".to_string(),
+        }
     }
 
     fn ui(&mut self, f: &mut Frame) {
+        self.selected_disk = self
+            .selected_disk
+            .min(self.system_info.disks.len().saturating_sub(1));
+
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .margin(1)
@@ -144,12 +321,16 @@ impl App {
             .split(f.size());
 
         // Title with tabs
-        let tab_titles = [
+        let mut tab_titles = vec![
             ("1: Overview", self.current_tab == Tab::Overview),
-            ("2: Memory", self.current_tab == Tab::Memory),
-            ("3: Disks", self.current_tab == Tab::Disks),
-            ("4: Network", self.current_tab == Tab::Network),
+            ("2: CPU", self.current_tab == Tab::Cpu),
+            ("3: Memory", self.current_tab == Tab::Memory),
+            ("4: Disks", self.current_tab == Tab::Disks),
+            ("5: Network", self.current_tab == Tab::Network),
         ];
+        if self.usage.is_some() {
+            tab_titles.push(("6: Usage", self.current_tab == Tab::Usage));
+        }
 
         let tabs_text: Vec<String> = tab_titles
             .iter()
@@ -162,22 +343,33 @@ impl App {
             })
             .collect();
 
-        let title = Paragraph::new(format!("🖥️  System Index - {}", tabs_text.join(" | ")))
-            .style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            )
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL));
+        let refreshing = if self.pending.is_empty() {
+            ""
+        } else {
+            "  ⟳ refreshing…"
+        };
+        let title = Paragraph::new(format!(
+            "🖥️  System Index - {}{}",
+            tabs_text.join(" | "),
+            refreshing
+        ))
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
         f.render_widget(title, chunks[0]);
 
         // Content based on current tab
         match self.current_tab {
             Tab::Overview => self.render_overview(f, chunks[1]),
+            Tab::Cpu => self.render_cpu(f, chunks[1]),
             Tab::Memory => self.render_memory(f, chunks[1]),
             Tab::Disks => self.render_disks(f, chunks[1]),
             Tab::Network => self.render_network(f, chunks[1]),
+            Tab::Usage => self.render_usage(f, chunks[1]),
         }
 
         // Status bar
@@ -195,9 +387,17 @@ impl App {
             format!("💻 OS: {} {}", info.os_name, info.os_version),
             format!("🔧 Kernel: {}", info.kernel_version),
             format!("⏰ Uptime: {}", SystemInfo::format_uptime(info.uptime)),
+            format!("📦 Environment: {}", info.environment.describe()),
             String::new(),
             format!("⚙️  CPU: {}", info.cpu_brand),
             format!("📊 CPU Cores: {}", info.cpu_count),
+            format!("🔥 CPU Usage: {:.1}%", info.cpu.global_usage),
+            format!(
+                "📉 Load Average: {:.2}, {:.2}, {:.2}",
+                info.cpu.load_average.one,
+                info.cpu.load_average.five,
+                info.cpu.load_average.fifteen
+            ),
             String::new(),
             format!(
                 "💾 Total Memory: {}",
@@ -218,30 +418,137 @@ impl App {
             ),
             format!("📊 Used Swap: {}", SystemInfo::format_bytes(info.used_swap)),
             String::new(),
+        ];
+
+        if let Some(cgroup) = &info.cgroup {
+            items.push(format!(
+                "📦 cgroup {} limits ({}):",
+                cgroup.version, cgroup.path
+            ));
+            for line in cgroup.summary_lines(info.cpu_count, info.total_memory) {
+                items.push(format!("   {}", line));
+            }
+            items.push(String::new());
+        }
+
+        items.extend([
             format!("💿 Disks: {}", info.disks.len()),
             format!("🌐 Network Interfaces: {}", info.networks.len()),
-        ];
+        ]);
 
         // Add network details if available
         if let Some(local_ip) = &info.network_details.local_ip {
             items.push(format!("🏠 Local IP: {}", local_ip));
         }
-        if let Some(public_ip) = &info.network_details.public_ip {
-            items.push(format!("🌍 Public IP: {}", public_ip));
-        }
+        items.push(format!(
+            "🌍 Public IP: {}",
+            info.network_details.public_ip_display()
+        ));
 
         items.push(format!("📋 Running Processes: {}", info.processes_count));
 
+        if info.pressure.is_available() {
+            items.push(String::new());
+            items.push("⏳ Pressure (avg10/avg60/avg300):".to_string());
+            for (resource, pressure) in info.pressure.entries() {
+                items.push(format!("   {:<8} {}", resource, pressure.summary()));
+            }
+        }
+
         let list_items: Vec<ListItem> = items
             .iter()
             .map(|item| ListItem::new(item.as_str()))
             .collect();
 
+        let list = List::new(list_items)
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "System Overview ({})",
+                self.staleness(&Section::ALL)
+            )))
+            .style(Style::default().fg(Color::White));
+
+        f.render_widget(list, area);
+    }
+
+    fn render_cpu(&self, f: &mut Frame, area: ratatui::layout::Rect) {
+        let cpu = &self.system_info.cpu;
+
+        let mut items = vec![
+            format!("CPU:          {}", self.system_info.cpu_brand),
+            format!("Cores:        {}", self.system_info.cpu_count),
+            format!("Global Usage: {:.1}%", cpu.global_usage),
+            format!(
+                "Usage Bar:    [{}]",
+                Self::create_progress_bar(cpu.global_usage as u32)
+            ),
+            format!(
+                "Load Average: {:.2} (1m), {:.2} (5m), {:.2} (15m)",
+                cpu.load_average.one, cpu.load_average.five, cpu.load_average.fifteen
+            ),
+            String::new(),
+        ];
+
+        if let Some(times) = &cpu.times {
+            items.push("═══ CPU TIME BREAKDOWN ═══".to_string());
+            let breakdown: Vec<String> = times
+                .entries()
+                .iter()
+                .map(|(state, percent)| format!("{} {:.1}%", state, percent))
+                .collect();
+            // Two rows of five states keep the breakdown compact
+            for row in breakdown.chunks(5) {
+                items.push(row.join("  "));
+            }
+            items.push(String::new());
+        }
+
+        if let Some(topology) = &cpu.topology {
+            items.push("═══ CPU TOPOLOGY ═══".to_string());
+            items.extend(topology.tree_lines());
+            items.push(String::new());
+        }
+
+        items.push("═══ PER-CORE USAGE ═══".to_string());
+
+        for core in &cpu.cores {
+            let limits = match (core.min_frequency_mhz, core.max_frequency_mhz) {
+                (Some(min), Some(max)) => format!(" ({}-{} MHz)", min, max),
+                _ => String::new(),
+            };
+            items.push(format!(
+                "{:<7} [{}] {:>5.1}%  {} MHz{}",
+                core.name,
+                Self::create_progress_bar(core.usage as u32),
+                core.usage,
+                core.frequency_mhz,
+                limits
+            ));
+        }
+
+        if cpu.cores.is_empty() {
+            items.push("No CPU cores found.".to_string());
+        }
+
+        let list_items: Vec<ListItem> = items
+            .iter()
+            .map(|item| {
+                if item.starts_with("═══") {
+                    ListItem::new(item.as_str()).style(
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    ListItem::new(item.as_str())
+                }
+            })
+            .collect();
+
         let list = List::new(list_items)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("System Overview"),
+                    .title(format!("CPU Details ({})", self.staleness(&[Section::Cpu]))),
             )
             .style(Style::default().fg(Color::White));
 
@@ -251,9 +558,10 @@ impl App {
     fn render_memory(&self, f: &mut Frame, area: ratatui::layout::Rect) {
         let info = &self.system_info;
 
+        let meminfo = info.memory.meminfo;
         let total_mem = info.total_memory;
         let used_mem = info.used_memory;
-        let free_mem = total_mem - used_mem;
+        let free_mem = meminfo.map_or(total_mem.saturating_sub(used_mem), |m| m.free);
         let mem_usage_percent = if total_mem > 0 {
             (used_mem as f64 / total_mem as f64 * 100.0) as u32
         } else {
@@ -269,7 +577,7 @@ impl App {
             0
         };
 
-        let items = vec![
+        let mut items = vec![
             "═══ RAM MEMORY ═══".to_string(),
             format!("Total:     {}", SystemInfo::format_bytes(total_mem)),
             format!(
@@ -283,6 +591,39 @@ impl App {
                 Self::create_progress_bar(mem_usage_percent)
             ),
             String::new(),
+        ];
+
+        if let Some(meminfo) = &meminfo {
+            items.push("═══ RAM BREAKDOWN ═══".to_string());
+            items.push(format!(
+                "Available: {}",
+                SystemInfo::format_bytes(meminfo.available)
+            ));
+            items.push(format!(
+                "Split:     [{}]",
+                meminfo.stacked_bar(PROGRESS_BAR_WIDTH)
+            ));
+            items.push(format!("           {}", meminfo.legend()));
+            items.push(format!(
+                "Shared: {}  Dirty: {}  Writeback: {}",
+                SystemInfo::format_bytes(meminfo.shared),
+                SystemInfo::format_bytes(meminfo.dirty),
+                SystemInfo::format_bytes(meminfo.writeback)
+            ));
+            items.push(format!(
+                "Slab: {} reclaimable, {} unreclaimable",
+                SystemInfo::format_bytes(meminfo.slab_reclaimable),
+                SystemInfo::format_bytes(meminfo.slab_unreclaimable)
+            ));
+            items.push(format!(
+                "Committed: {} of {} limit",
+                SystemInfo::format_bytes(meminfo.committed),
+                SystemInfo::format_bytes(meminfo.commit_limit)
+            ));
+            items.push(String::new());
+        }
+
+        items.extend([
             "═══ SWAP MEMORY ═══".to_string(),
             format!("Total:     {}", SystemInfo::format_bytes(total_swap)),
             format!(
@@ -295,7 +636,8 @@ impl App {
                 "Usage Bar: [{}]",
                 Self::create_progress_bar(swap_usage_percent)
             ),
-        ];
+        ]);
+        items.extend(info.memory.section_lines());
 
         let list_items: Vec<ListItem> = items
             .iter()
@@ -313,11 +655,10 @@ impl App {
             .collect();
 
         let list = List::new(list_items)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Memory Details"),
-            )
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "Memory Details ({})",
+                self.staleness(&[Section::Memory])
+            )))
             .style(Style::default().fg(Color::White));
 
         f.render_widget(list, area);
@@ -325,7 +666,7 @@ impl App {
 
     /// Create a progress bar string for the given percentage
     fn create_progress_bar(percent: u32) -> String {
-        let filled = (percent / 2) as usize;
+        let filled = (percent.min(100) / 2) as usize;
         let empty = PROGRESS_BAR_WIDTH - filled;
         format!("{}{}", "█".repeat(filled), "░".repeat(empty))
     }
@@ -334,6 +675,8 @@ impl App {
         let info = &self.system_info;
 
         let mut items = vec!["Mounted Disks:".to_string(), String::new()];
+        // Lines of the selected disk, kept in view while scrolling
+        let mut selected_lines = 0..0;
 
         for (idx, disk) in info.disks.iter().enumerate() {
             let used_space = disk.total_space - disk.available_space;
@@ -343,10 +686,23 @@ impl App {
                 0
             };
 
-            items.push(format!("═══ Disk {} ═══", idx + 1));
+            let selected = idx == self.selected_disk;
+            let marker = if selected { "▶ " } else { "" };
+            let header = items.len();
+            items.push(format!("═══ {}Disk {} ═══", marker, idx + 1));
             items.push(format!("Name:       {}", disk.name));
             items.push(format!("Mount:      {}", disk.mount_point));
             items.push(format!("Filesystem: {}", disk.file_system));
+            if let Some(source) = &disk.source {
+                items.push(format!(
+                    "Source:     {} ({})",
+                    source,
+                    disk.dev.as_deref().unwrap_or("?")
+                ));
+            }
+            if !disk.mount_options.is_empty() {
+                items.push(format!("Options:    {}", disk.mount_options.join(",")));
+            }
             items.push(format!(
                 "Total:      {}",
                 SystemInfo::format_bytes(disk.total_space)
@@ -364,6 +720,33 @@ impl App {
                 "Usage Bar:  [{}]",
                 Self::create_progress_bar(usage_percent)
             ));
+            if let Some(inodes) = &disk.inodes {
+                items.push(format!(
+                    "Inodes:     {} / {} used, {} free ({:.1}%)",
+                    inodes.used,
+                    inodes.total,
+                    inodes.free,
+                    inodes.usage_percent()
+                ));
+                items.push(format!(
+                    "Inode Bar:  [{}]",
+                    Self::create_progress_bar(inodes.usage_percent() as u32)
+                ));
+            }
+            if let Some(forecast) = &disk.forecast {
+                let critical = self
+                    .disk_history
+                    .as_ref()
+                    .is_some_and(|file| forecast.full_within(file.critical_within));
+                if critical {
+                    items.push(format!("⚠️  Forecast: {}", forecast.summary()));
+                } else {
+                    items.push(format!("Forecast:   {}", forecast.summary()));
+                }
+            }
+            if selected {
+                selected_lines = header..items.len();
+            }
             items.push(String::new());
         }
 
@@ -371,6 +754,29 @@ impl App {
             items.push("No disks found.".to_string());
         }
 
+        if !info.block_devices.is_empty() {
+            items.push("═══ Block Devices ═══".to_string());
+            items.extend(BlockDevice::tree_lines(&info.block_devices));
+            items.push(String::new());
+        }
+
+        if !info.raid_arrays.is_empty() {
+            items.push("═══ Software RAID ═══".to_string());
+            for array in &info.raid_arrays {
+                let mut lines = array.summary_lines();
+                if array.is_degraded() {
+                    lines[0] = format!("⚠️  {}", lines[0]);
+                }
+                items.extend(lines);
+            }
+            items.push(String::new());
+        }
+
+        if !info.disk_io.is_empty() {
+            items.push("═══ I/O Activity ═══".to_string());
+            items.extend(DiskIoStats::table_lines(&info.disk_io));
+        }
+
         let list_items: Vec<ListItem> = items
             .iter()
             .map(|item| {
@@ -380,6 +786,9 @@ impl App {
                             .fg(Color::Cyan)
                             .add_modifier(Modifier::BOLD),
                     )
+                } else if item.starts_with("⚠️") {
+                    ListItem::new(item.as_str())
+                        .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
                 } else {
                     ListItem::new(item.as_str())
                 }
@@ -387,14 +796,24 @@ impl App {
             .collect();
 
         let list = List::new(list_items)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Disk Information"),
-            )
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "Disk Information ({}; ↑/↓ select, Enter analyze usage)",
+                self.staleness(&[Section::Disks])
+            )))
             .style(Style::default().fg(Color::White));
 
-        f.render_widget(list, area);
+        // Scroll the selected disk's header to the top once its lines no longer
+        // fit below the start of the list
+        let visible = usize::from(area.height.saturating_sub(2));
+        let offset = if selected_lines.end <= visible {
+            0
+        } else {
+            selected_lines.start
+        };
+        let mut state = ListState::default()
+            .with_offset(offset)
+            .with_selected(Some(selected_lines.start));
+        f.render_stateful_widget(list, area, &mut state);
     }
 
     fn render_network(&self, f: &mut Frame, area: ratatui::layout::Rect) {
@@ -409,24 +828,50 @@ impl App {
             items.push("🏠 Local IP:    Not available".to_string());
         }
 
-        if let Some(public_ip) = &info.network_details.public_ip {
-            items.push(format!("🌍 Public IP:   {}", public_ip));
-        } else {
-            items.push("🌍 Public IP:   Not available".to_string());
-        }
-
-        if let Some(bandwidth) = info.network_details.bandwidth_mbps {
-            items.push(format!("⚡ Bandwidth:   {:.2} Mbps", bandwidth));
-        } else {
-            items.push("⚡ Bandwidth:   Not available".to_string());
-        }
+        items.push(format!(
+            "🌍 Public IP:   {}",
+            info.network_details.public_ip_display()
+        ));
+        items.push(format!(
+            "⚡ Bandwidth:   {}",
+            info.network_details.bandwidth_display()
+        ));
 
         items.push(String::new());
         items.push("═══ NETWORK INTERFACES ═══".to_string());
         items.push(String::new());
 
         for (idx, network) in info.networks.iter().enumerate() {
-            items.push(format!("Interface {}: {}", idx + 1, network.interface_name));
+            let link = network.link.summary();
+            // Errors or drops during the last sample point at a faulty NIC or cable
+            let marker = if network.rates.as_ref().is_some_and(|r| r.has_faults()) {
+                "⚠️  "
+            } else {
+                ""
+            };
+            if link.is_empty() {
+                items.push(format!(
+                    "{}Interface {}: {}",
+                    marker,
+                    idx + 1,
+                    network.interface_name
+                ));
+            } else {
+                items.push(format!(
+                    "{}Interface {}: {} ({})",
+                    marker,
+                    idx + 1,
+                    network.interface_name,
+                    link
+                ));
+            }
+            if let Some(mac) = &network.link.mac_address {
+                items.push(format!("  MAC:         {}", mac));
+            }
+            for address in &network.addresses {
+                let family = if address.is_ipv4() { "IPv4" } else { "IPv6" };
+                items.push(format!("  {}:        {}", family, address));
+            }
             items.push(format!(
                 "  Received:    {}",
                 SystemInfo::format_bytes(network.received_bytes)
@@ -439,6 +884,19 @@ impl App {
                 "  Total:       {}",
                 SystemInfo::format_bytes(network.received_bytes + network.transmitted_bytes)
             ));
+            if let Some(rates) = &network.rates {
+                let line = format!("  Rate:        {}", rates.summary());
+                if rates.has_faults() {
+                    items.push(format!("⚠️{}", line));
+                } else {
+                    items.push(line);
+                }
+            }
+            if let Some(stats) = &network.stats {
+                for (label, value) in stats.entries() {
+                    items.push(format!("  {:<13}{}", format!("{}:", label), value));
+                }
+            }
             items.push(String::new());
         }
 
@@ -455,6 +913,9 @@ impl App {
                             .fg(Color::Cyan)
                             .add_modifier(Modifier::BOLD),
                     )
+                } else if item.starts_with("⚠️") {
+                    ListItem::new(item.as_str())
+                        .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
                 } else {
                     ListItem::new(item.as_str())
                 }
@@ -462,13 +923,74 @@ impl App {
             .collect();
 
         let list = List::new(list_items)
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "Network Information (interfaces {}, details {})",
+                self.staleness(&[Section::Networks]),
+                self.staleness(&[Section::NetworkDetails])
+            )))
+            .style(Style::default().fg(Color::White));
+
+        f.render_widget(list, area);
+    }
+
+    fn render_usage(&self, f: &mut Frame, area: ratatui::layout::Rect) {
+        let Some(usage) = &self.usage else {
+            return;
+        };
+        let header = usage.header_lines();
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(header.len() as u16 + 2),
+                Constraint::Min(0),
+            ])
+            .split(area);
+
+        let header_items: Vec<ListItem> = header
+            .iter()
+            .map(|line| {
+                if line.starts_with("⚠️") {
+                    ListItem::new(line.as_str())
+                        .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+                } else {
+                    ListItem::new(line.as_str())
+                }
+            })
+            .collect();
+        let header = List::new(header_items)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title("Network Information"),
+                    .title(format!("Disk Usage of {}", usage.path().display())),
             )
             .style(Style::default().fg(Color::White));
+        f.render_widget(header, chunks[0]);
 
-        f.render_widget(list, area);
+        let rows = usage.rows();
+        let list_items: Vec<ListItem> = rows
+            .iter()
+            .map(|row| {
+                if row.is_dir {
+                    ListItem::new(row.line.as_str()).style(Style::default().fg(Color::Cyan))
+                } else {
+                    ListItem::new(row.line.as_str())
+                }
+            })
+            .collect();
+        let list = List::new(list_items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Largest Entries (Enter open, ←/Backspace up, Esc back to disks)"),
+            )
+            .style(Style::default().fg(Color::White))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("▶ ");
+
+        let mut state = ListState::default();
+        if !rows.is_empty() {
+            state.select(Some(usage.selected()));
+        }
+        f.render_stateful_widget(list, chunks[1], &mut state);
     }
 }
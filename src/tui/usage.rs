@@ -0,0 +1,316 @@
+use crate::models::SystemInfo;
+use crate::usage::{self, DirUsage, Scan, ScanOptions};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+/// Width of the per-row usage bars in characters
+const BAR_WIDTH: usize = 20;
+
+/// State of the drill-down disk usage view
+///
+/// The scan runs on a background thread and is cancelled when the view is dropped.
+pub struct UsageView {
+    path: PathBuf,
+    state: ScanState,
+    cancel: Arc<AtomicBool>,
+    /// Child indices leading from the scanned root to the shown directory
+    trail: Vec<usize>,
+    /// Selected row of the shown directory
+    selected: usize,
+}
+
+enum ScanState {
+    Running {
+        started: Instant,
+        result: Receiver<crate::Result<Option<Scan>>>,
+    },
+    Done(Scan),
+    Failed(String),
+}
+
+/// One row of the shown directory
+pub struct Row {
+    pub line: String,
+    pub is_dir: bool,
+}
+
+impl UsageView {
+    /// Start scanning `path` in the background
+    pub fn start(path: PathBuf) -> Self {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (tx, result) = mpsc::channel();
+        let (scan_path, scan_cancel) = (path.clone(), Arc::clone(&cancel));
+        thread::spawn(move || {
+            let _ = tx.send(usage::scan(
+                &scan_path,
+                &ScanOptions::default(),
+                &scan_cancel,
+            ));
+        });
+
+        Self {
+            path,
+            state: ScanState::Running {
+                started: Instant::now(),
+                result,
+            },
+            cancel,
+            trail: Vec::new(),
+            selected: 0,
+        }
+    }
+
+    /// Pick up the scan result once the background thread is done
+    pub fn poll(&mut self) {
+        let ScanState::Running { result, .. } = &self.state else {
+            return;
+        };
+        self.state = match result.try_recv() {
+            Ok(Ok(Some(scan))) => ScanState::Done(scan),
+            Ok(Ok(None)) => ScanState::Failed("scan cancelled".to_string()),
+            Ok(Err(err)) => ScanState::Failed(format!("{:#}", err)),
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                ScanState::Failed("scan stopped unexpectedly".to_string())
+            }
+        };
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Summary lines above the rows: scan progress, an error, or totals of the shown directory
+    pub fn header_lines(&self) -> Vec<String> {
+        match &self.state {
+            ScanState::Running { started, .. } => vec![
+                format!("Scanning {}…", self.path.display()),
+                format!("Elapsed: {}s", started.elapsed().as_secs()),
+            ],
+            ScanState::Failed(err) => {
+                vec![format!("⚠️  Cannot scan {}: {}", self.path.display(), err)]
+            }
+            ScanState::Done(scan) => {
+                let dir = self.current().unwrap_or(&scan.root);
+                let mut lines = vec![
+                    format!("Directory: {}", dir.path.display()),
+                    format!(
+                        "Size:      {} in {} files, {} directories",
+                        SystemInfo::format_bytes(dir.size_bytes),
+                        dir.file_count,
+                        dir.dir_count
+                    ),
+                ];
+                if scan.unreadable > 0 {
+                    lines.push(format!(
+                        "⚠️  {} unreadable directories, sizes are a lower bound",
+                        scan.unreadable
+                    ));
+                }
+                lines
+            }
+        }
+    }
+
+    /// Subdirectories of the shown directory followed by its largest files
+    pub fn rows(&self) -> Vec<Row> {
+        let Some(dir) = self.current() else {
+            return Vec::new();
+        };
+        let row = |size: u64, name: String, is_dir: bool| {
+            let percent = if dir.size_bytes > 0 {
+                size as f64 / dir.size_bytes as f64 * 100.0
+            } else {
+                0.0
+            };
+            Row {
+                line: format!(
+                    "{:>10} {:>5.1}% [{}] {}",
+                    SystemInfo::format_bytes(size),
+                    percent,
+                    bar(percent),
+                    name
+                ),
+                is_dir,
+            }
+        };
+
+        let mut rows: Vec<Row> = dir
+            .children
+            .iter()
+            .map(|child| row(child.size_bytes, format!("{}/", child.name()), true))
+            .collect();
+        rows.extend(dir.files.iter().map(|file| {
+            let name = file
+                .path
+                .file_name()
+                .map_or_else(String::new, |name| name.to_string_lossy().to_string());
+            row(file.size_bytes, name, false)
+        }));
+        rows
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn select_next(&mut self) {
+        let count = self.row_count();
+        if count > 0 {
+            self.selected = (self.selected + 1).min(count - 1);
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Descend into the selected directory; files are left alone
+    pub fn enter(&mut self) {
+        let is_dir = self
+            .current()
+            .is_some_and(|dir| self.selected < dir.children.len());
+        if is_dir {
+            self.trail.push(self.selected);
+            self.selected = 0;
+        }
+    }
+
+    /// Go up one level, selecting the directory just left; `false` at the scanned root
+    pub fn leave(&mut self) -> bool {
+        match self.trail.pop() {
+            Some(index) => {
+                self.selected = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn current(&self) -> Option<&DirUsage> {
+        let ScanState::Done(scan) = &self.state else {
+            return None;
+        };
+        self.trail
+            .iter()
+            .try_fold(&scan.root, |dir, &index| dir.children.get(index))
+    }
+
+    fn row_count(&self) -> usize {
+        self.current()
+            .map_or(0, |dir| dir.children.len() + dir.files.len())
+    }
+}
+
+impl Drop for UsageView {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+fn bar(percent: f64) -> String {
+    let filled = ((percent.clamp(0.0, 100.0) / 100.0) * BAR_WIDTH as f64).round() as usize;
+    format!("{}{}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usage::FileUsage;
+
+    fn dir(path: &str, size_bytes: u64, children: Vec<DirUsage>) -> DirUsage {
+        DirUsage {
+            path: PathBuf::from(path),
+            size_bytes,
+            children,
+            ..DirUsage::default()
+        }
+    }
+
+    fn finished_view() -> UsageView {
+        let mut root = dir(
+            "/srv",
+            1000,
+            vec![
+                dir("/srv/data", 750, vec![dir("/srv/data/db", 500, vec![])]),
+                dir("/srv/www", 150, vec![]),
+            ],
+        );
+        root.files.push(FileUsage {
+            path: PathBuf::from("/srv/dump.sql"),
+            size_bytes: 100,
+        });
+        UsageView {
+            path: PathBuf::from("/srv"),
+            state: ScanState::Done(Scan {
+                root,
+                unreadable: 0,
+                elapsed_ms: 5,
+            }),
+            cancel: Arc::new(AtomicBool::new(false)),
+            trail: Vec::new(),
+            selected: 0,
+        }
+    }
+
+    #[test]
+    fn test_rows() {
+        let view = finished_view();
+        let rows = view.rows();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(
+            rows[0].line,
+            format!(
+                "  750.00 B  75.0% [{}{}] data/",
+                "█".repeat(15),
+                "░".repeat(5)
+            )
+        );
+        assert!(rows[1].is_dir);
+        assert!(!rows[2].is_dir);
+        assert!(rows[2]
+            .line
+            .ends_with("10.0% [██░░░░░░░░░░░░░░░░░░] dump.sql"));
+    }
+
+    #[test]
+    fn test_drill_down() {
+        let mut view = finished_view();
+        view.enter();
+        assert_eq!(view.header_lines()[0], "Directory: /srv/data");
+        assert_eq!(view.rows().len(), 1);
+
+        view.select_next();
+        assert_eq!(view.selected(), 0);
+        view.enter();
+        assert_eq!(view.header_lines()[0], "Directory: /srv/data/db");
+        assert!(view.rows().is_empty());
+
+        assert!(view.leave());
+        assert!(view.leave());
+        assert!(!view.leave());
+
+        // Files cannot be entered
+        view.select_next();
+        view.select_next();
+        view.select_next();
+        assert_eq!(view.selected(), 2);
+        view.enter();
+        assert_eq!(view.header_lines()[0], "Directory: /srv");
+    }
+
+    #[test]
+    fn test_failed_scan() {
+        let mut view = UsageView::start(PathBuf::from("/nonexistent/mount"));
+        while matches!(view.state, ScanState::Running { .. }) {
+            view.poll();
+            thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert!(view.header_lines()[0].starts_with("⚠️  Cannot scan /nonexistent/mount: "));
+        assert!(view.rows().is_empty());
+    }
+}
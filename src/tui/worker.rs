@@ -0,0 +1,154 @@
+use crate::models::{CollectOptions, Section, SystemInfo};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often local sections are re-collected
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often external network probes are re-run
+const PROBE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A snapshot published by a background worker
+pub struct Update {
+    /// Collected information; only the fields of `sections` are meaningful
+    pub info: SystemInfo,
+    /// Sections contained in this update
+    pub sections: Vec<Section>,
+    /// When collection finished
+    pub collected_at: Instant,
+}
+
+/// Handle to the background threads collecting system information
+///
+/// Local sections are gathered on one thread every [`REFRESH_INTERVAL`].
+/// When external probes are enabled, network details are gathered on a second
+/// thread every [`PROBE_INTERVAL`] so slow lookups never delay the rest.
+/// The threads exit once the collector is dropped.
+pub struct Collector {
+    triggers: Vec<Sender<()>>,
+    updates: Receiver<Update>,
+}
+
+impl Collector {
+    /// Start collecting the sections selected by `options`
+    pub fn spawn(options: &CollectOptions) -> Self {
+        let (update_tx, updates) = mpsc::channel();
+        let mut triggers = Vec::new();
+
+        let probes = options.probes_public_ip() || options.probes_bandwidth();
+        if probes {
+            let local = options
+                .clone()
+                .without_section(Section::NetworkDetails)
+                .network_probes(false);
+            let remote = CollectOptions {
+                sections: vec![Section::NetworkDetails],
+                ..options.clone()
+            };
+            triggers.push(Self::spawn_worker(
+                local,
+                REFRESH_INTERVAL,
+                update_tx.clone(),
+            ));
+            triggers.push(Self::spawn_worker(remote, PROBE_INTERVAL, update_tx));
+        } else {
+            triggers.push(Self::spawn_worker(
+                options.clone(),
+                REFRESH_INTERVAL,
+                update_tx,
+            ));
+        }
+
+        Self { triggers, updates }
+    }
+
+    /// Ask every worker to collect again immediately
+    pub fn request_refresh(&self) {
+        for trigger in &self.triggers {
+            let _ = trigger.send(());
+        }
+    }
+
+    /// Take the next published update, if any
+    pub fn try_recv(&self) -> Option<Update> {
+        self.updates.try_recv().ok()
+    }
+
+    fn spawn_worker(
+        options: CollectOptions,
+        interval: Duration,
+        updates: Sender<Update>,
+    ) -> Sender<()> {
+        let (trigger_tx, trigger_rx) = mpsc::channel();
+
+        thread::spawn(move || loop {
+            let update = Update {
+                info: SystemInfo::collect_with(&options),
+                sections: options.sections.clone(),
+                collected_at: Instant::now(),
+            };
+            if updates.send(update).is_err() {
+                break;
+            }
+
+            match trigger_rx.recv_timeout(interval) {
+                Ok(()) => {
+                    // Coalesce refresh requests queued while collecting
+                    while trigger_rx.try_recv().is_ok() {}
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        });
+
+        trigger_tx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wait_for_update(collector: &Collector) -> Update {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            if let Some(update) = collector.try_recv() {
+                return update;
+            }
+            assert!(Instant::now() < deadline, "no update published");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_collector_publishes_snapshots() {
+        let collector = Collector::spawn(&CollectOptions::empty().section(Section::Cpu));
+
+        let first = wait_for_update(&collector);
+        assert_eq!(first.sections, vec![Section::Cpu]);
+        assert!(first.info.cpu_count > 0);
+
+        collector.request_refresh();
+        let second = wait_for_update(&collector);
+        assert!(second.collected_at >= first.collected_at);
+    }
+
+    #[test]
+    fn test_probes_run_on_separate_worker() {
+        let options = CollectOptions::empty()
+            .sections([Section::Cpu, Section::NetworkDetails])
+            .network_probes(true)
+            .public_ip_resolvers(Vec::new())
+            .bandwidth_urls(Vec::new());
+        let collector = Collector::spawn(&options);
+
+        assert_eq!(collector.triggers.len(), 2);
+        let mut sections = Vec::new();
+        while sections.len() < 2 {
+            sections.extend(wait_for_update(&collector).sections);
+        }
+        assert!(sections.contains(&Section::Cpu));
+        assert!(sections.contains(&Section::NetworkDetails));
+    }
+}
@@ -0,0 +1,451 @@
+use crate::models::SystemInfo;
+use crate::Result;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Default number of entries in each top-N list
+pub const DEFAULT_TOP: usize = 10;
+
+/// Options for a directory usage scan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanOptions {
+    /// Largest files kept per directory; global top-N lists can be at most this long
+    pub top: usize,
+    /// Descend into other filesystems mounted below the scanned path
+    pub cross_filesystems: bool,
+    /// Worker threads walking the tree; defaults to the available parallelism
+    pub threads: usize,
+}
+
+/// Disk usage of a directory and everything below it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DirUsage {
+    pub path: PathBuf,
+    /// Space allocated on disk, in bytes; hard-linked files are counted once
+    pub size_bytes: u64,
+    pub file_count: u64,
+    /// Directories below this one, not counting itself
+    pub dir_count: u64,
+    /// Subdirectories, largest first
+    pub children: Vec<DirUsage>,
+    /// Largest files directly in this directory, largest first
+    pub files: Vec<FileUsage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileUsage {
+    pub path: PathBuf,
+    pub size_bytes: u64,
+}
+
+/// Result of scanning a path, for the `usage` command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageReport {
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    pub file_count: u64,
+    pub dir_count: u64,
+    /// Directories that could not be read, e.g. for lack of permission
+    pub unreadable: u64,
+    pub elapsed_ms: u64,
+    /// Largest directories below the path, largest first
+    pub largest_directories: Vec<FileUsage>,
+    pub largest_files: Vec<FileUsage>,
+}
+
+/// Result of a completed scan
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scan {
+    pub root: DirUsage,
+    pub unreadable: u64,
+    pub elapsed_ms: u64,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            top: DEFAULT_TOP,
+            cross_filesystems: false,
+            threads: std::thread::available_parallelism().map_or(4, |n| n.get()),
+        }
+    }
+}
+
+/// Walk `path` in parallel and aggregate sizes per directory
+///
+/// Symlinks are not followed. Returns `None` when `cancel` is set before the
+/// walk finishes.
+pub fn scan(path: &Path, options: &ScanOptions, cancel: &AtomicBool) -> Result<Option<Scan>> {
+    let metadata =
+        std::fs::metadata(path).with_context(|| format!("cannot read {}", path.display()))?;
+    anyhow::ensure!(metadata.is_dir(), "{} is not a directory", path.display());
+
+    let started = Instant::now();
+    let walker = Walker {
+        options,
+        cancel,
+        device: (!options.cross_filesystems)
+            .then(|| device_of(&metadata))
+            .flatten(),
+        // The calling thread is a worker too
+        idle_threads: AtomicUsize::new(options.threads.saturating_sub(1)),
+        hard_links: Mutex::new(HashSet::new()),
+        unreadable: AtomicU64::new(0),
+    };
+    let root = walker.walk(path.to_path_buf());
+    if cancel.load(Ordering::Relaxed) {
+        return Ok(None);
+    }
+
+    Ok(Some(Scan {
+        root,
+        unreadable: walker.unreadable.into_inner(),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    }))
+}
+
+struct Walker<'a> {
+    options: &'a ScanOptions,
+    cancel: &'a AtomicBool,
+    /// Filesystem to stay on, when not crossing mounts
+    device: Option<u64>,
+    idle_threads: AtomicUsize,
+    /// `(device, inode)` of multiply-linked files already counted
+    hard_links: Mutex<HashSet<(u64, u64)>>,
+    unreadable: AtomicU64,
+}
+
+impl Walker<'_> {
+    fn walk(&self, path: PathBuf) -> DirUsage {
+        let mut usage = DirUsage {
+            path,
+            ..DirUsage::default()
+        };
+        if self.cancel.load(Ordering::Relaxed) {
+            return usage;
+        }
+        let Ok(entries) = std::fs::read_dir(&usage.path) else {
+            self.unreadable.fetch_add(1, Ordering::Relaxed);
+            return usage;
+        };
+
+        let mut subdirs = Vec::new();
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                if self.device.is_none() || device_of(&metadata) == self.device {
+                    subdirs.push(entry.path());
+                }
+            } else if self.first_link(&metadata) {
+                let size_bytes = allocated_size(&metadata);
+                usage.size_bytes += size_bytes;
+                usage.file_count += 1;
+                usage.files.push(FileUsage {
+                    path: entry.path(),
+                    size_bytes,
+                });
+            }
+        }
+        keep_largest(&mut usage.files, self.options.top, |file| file.size_bytes);
+
+        usage.children = std::thread::scope(|scope| {
+            // Hand subdirectories to idle threads, walking the rest here
+            let handles: Vec<_> = subdirs
+                .into_iter()
+                .map(|subdir| {
+                    if self.try_claim_thread() {
+                        Err(scope.spawn(move || {
+                            let child = self.walk(subdir);
+                            self.idle_threads.fetch_add(1, Ordering::Relaxed);
+                            child
+                        }))
+                    } else {
+                        Ok(self.walk(subdir))
+                    }
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.unwrap_or_else(|spawned| spawned.join().unwrap_or_default()))
+                .collect()
+        });
+        for child in &usage.children {
+            usage.size_bytes += child.size_bytes;
+            usage.file_count += child.file_count;
+            usage.dir_count += child.dir_count + 1;
+        }
+        usage
+            .children
+            .sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then(a.path.cmp(&b.path)));
+        usage
+    }
+
+    fn try_claim_thread(&self) -> bool {
+        self.idle_threads
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |idle| {
+                idle.checked_sub(1)
+            })
+            .is_ok()
+    }
+
+    /// Whether this is the first time a multiply-linked file is seen
+    #[cfg(unix)]
+    fn first_link(&self, metadata: &std::fs::Metadata) -> bool {
+        use std::os::unix::fs::MetadataExt;
+
+        metadata.nlink() <= 1
+            || self.hard_links.lock().map_or(true, |mut seen| {
+                seen.insert((metadata.dev(), metadata.ino()))
+            })
+    }
+
+    #[cfg(not(unix))]
+    fn first_link(&self, _metadata: &std::fs::Metadata) -> bool {
+        true
+    }
+}
+
+/// Blocks actually allocated, like `du`; sparse files count for less than their length
+#[cfg(unix)]
+fn allocated_size(metadata: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn allocated_size(metadata: &std::fs::Metadata) -> u64 {
+    metadata.len()
+}
+
+#[cfg(unix)]
+fn device_of(metadata: &std::fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.dev())
+}
+
+#[cfg(not(unix))]
+fn device_of(_metadata: &std::fs::Metadata) -> Option<u64> {
+    None
+}
+
+/// Sort largest first and truncate to `count`
+fn keep_largest<T>(items: &mut Vec<T>, count: usize, size: impl Fn(&T) -> u64) {
+    items.sort_by_key(|item| std::cmp::Reverse(size(item)));
+    items.truncate(count);
+}
+
+impl DirUsage {
+    /// Name of the last path component, or the whole path for the root
+    pub fn name(&self) -> String {
+        self.path.file_name().map_or_else(
+            || self.path.display().to_string(),
+            |name| name.to_string_lossy().to_string(),
+        )
+    }
+
+    /// The `count` largest directories below this one, at any depth
+    pub fn largest_directories(&self, count: usize) -> Vec<FileUsage> {
+        let mut directories = Vec::new();
+        let mut pending: Vec<&DirUsage> = self.children.iter().collect();
+        while let Some(dir) = pending.pop() {
+            directories.push(FileUsage {
+                path: dir.path.clone(),
+                size_bytes: dir.size_bytes,
+            });
+            pending.extend(&dir.children);
+        }
+        keep_largest(&mut directories, count, |dir| dir.size_bytes);
+        directories
+    }
+
+    /// The `count` largest files below this one, at any depth
+    ///
+    /// Exact as long as `count` does not exceed [`ScanOptions::top`].
+    pub fn largest_files(&self, count: usize) -> Vec<FileUsage> {
+        let mut files = Vec::new();
+        let mut pending = vec![self];
+        while let Some(dir) = pending.pop() {
+            files.extend(dir.files.iter().cloned());
+            pending.extend(&dir.children);
+        }
+        keep_largest(&mut files, count, |file| file.size_bytes);
+        files
+    }
+}
+
+impl FileUsage {
+    /// Table rows of size, share of `total` and path relative to `base`
+    pub fn table_lines(entries: &[FileUsage], total: u64, base: &Path) -> Vec<String> {
+        entries
+            .iter()
+            .map(|entry| {
+                let percent = if total > 0 {
+                    entry.size_bytes as f64 / total as f64 * 100.0
+                } else {
+                    0.0
+                };
+                let path = entry.path.strip_prefix(base).unwrap_or(&entry.path);
+                format!(
+                    "{:>10}  {:>5.1}%  {}",
+                    SystemInfo::format_bytes(entry.size_bytes),
+                    percent,
+                    path.display()
+                )
+            })
+            .collect()
+    }
+}
+
+impl UsageReport {
+    pub fn from_scan(scan: &Scan, top: usize) -> Self {
+        Self {
+            path: scan.root.path.clone(),
+            size_bytes: scan.root.size_bytes,
+            file_count: scan.root.file_count,
+            dir_count: scan.root.dir_count,
+            unreadable: scan.unreadable,
+            elapsed_ms: scan.elapsed_ms,
+            largest_directories: scan.root.largest_directories(top),
+            largest_files: scan.root.largest_files(top),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A scratch directory tree removed on drop
+    struct TempTree(PathBuf);
+
+    impl TempTree {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!(
+                "system-index-usage-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = std::fs::remove_dir_all(&root);
+            std::fs::create_dir_all(&root).unwrap();
+            Self(root)
+        }
+
+        fn file(&self, path: &str, size: usize) {
+            let path = self.0.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, vec![1u8; size]).unwrap();
+        }
+    }
+
+    impl Drop for TempTree {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn scan_tree(tree: &TempTree, threads: usize) -> Scan {
+        let options = ScanOptions {
+            threads,
+            ..ScanOptions::default()
+        };
+        scan(&tree.0, &options, &AtomicBool::new(false))
+            .unwrap()
+            .unwrap()
+    }
+
+    fn sample_tree(name: &str) -> TempTree {
+        let tree = TempTree::new(name);
+        tree.file("logs/app.log", 64 * 1024);
+        tree.file("logs/old/app.log.1", 256 * 1024);
+        tree.file("cache/blob", 16 * 1024);
+        tree.file("readme.txt", 100);
+        std::fs::create_dir_all(tree.0.join("empty")).unwrap();
+        tree
+    }
+
+    #[test]
+    fn test_aggregates_sizes_per_directory() {
+        let tree = sample_tree("aggregate");
+        let scan = scan_tree(&tree, 1);
+        let root = &scan.root;
+        assert_eq!(root.file_count, 4);
+        assert_eq!(root.dir_count, 4);
+        assert_eq!(scan.unreadable, 0);
+
+        let names: Vec<String> = root.children.iter().map(DirUsage::name).collect();
+        assert_eq!(names, ["logs", "cache", "empty"]);
+        let logs = &root.children[0];
+        assert_eq!(
+            logs.size_bytes,
+            logs.children[0].size_bytes + logs.files[0].size_bytes
+        );
+        assert_eq!(
+            root.size_bytes,
+            root.children.iter().map(|c| c.size_bytes).sum::<u64>() + root.files[0].size_bytes
+        );
+    }
+
+    #[test]
+    fn test_parallel_scan_matches_sequential() {
+        let tree = sample_tree("parallel");
+        for dir in 0..20 {
+            tree.file(&format!("many/{}/data", dir), 4096 * (dir + 1));
+        }
+        let sequential = scan_tree(&tree, 1).root;
+        let parallel = scan_tree(&tree, 8).root;
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn test_top_lists() {
+        let tree = sample_tree("top");
+        let report = UsageReport::from_scan(&scan_tree(&tree, 2), 2);
+
+        let files: Vec<&Path> = report
+            .largest_files
+            .iter()
+            .map(|f| f.path.strip_prefix(&tree.0).unwrap())
+            .collect();
+        assert_eq!(
+            files,
+            [Path::new("logs/old/app.log.1"), Path::new("logs/app.log")]
+        );
+
+        let dirs: Vec<&Path> = report
+            .largest_directories
+            .iter()
+            .map(|d| d.path.strip_prefix(&tree.0).unwrap())
+            .collect();
+        assert_eq!(dirs, [Path::new("logs"), Path::new("logs/old")]);
+    }
+
+    #[test]
+    fn test_table_lines() {
+        let entries = [FileUsage {
+            path: PathBuf::from("/var/log/journal"),
+            size_bytes: 1024 * 1024,
+        }];
+        assert_eq!(
+            FileUsage::table_lines(&entries, 4 * 1024 * 1024, Path::new("/var")),
+            ["   1.00 MB   25.0%  log/journal"]
+        );
+    }
+
+    #[test]
+    fn test_cancel_and_errors() {
+        let tree = sample_tree("cancel");
+        let cancelled = scan(&tree.0, &ScanOptions::default(), &AtomicBool::new(true)).unwrap();
+        assert!(cancelled.is_none());
+
+        let cancel = AtomicBool::new(false);
+        assert!(scan(&tree.0.join("readme.txt"), &ScanOptions::default(), &cancel).is_err());
+        assert!(scan(&tree.0.join("missing"), &ScanOptions::default(), &cancel).is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use system_index::models::SystemInfo;
+use system_index::models::{CollectOptions, Section, SystemInfo};
 
 #[test]
 fn test_system_info_collection() {
@@ -94,3 +94,23 @@ fn test_get_local_ip() {
     // but we can't guarantee it in all test environments
     let _ = result;
 }
+
+#[test]
+fn test_collect_with_selected_sections() {
+    let options = CollectOptions::empty().sections([Section::Cpu, Section::Memory]);
+    let info = SystemInfo::collect_with(&options);
+
+    assert!(info.cpu_count > 0, "CPU section should be collected");
+    assert!(info.total_memory > 0, "Memory section should be collected");
+    assert!(info.os_name.is_empty(), "System section should be skipped");
+    assert!(info.disks.is_empty(), "Disks section should be skipped");
+    assert!(
+        info.networks.is_empty(),
+        "Networks section should be skipped"
+    );
+    assert!(
+        info.network_details.local_ip.is_none(),
+        "Network details should be skipped"
+    );
+    assert_eq!(info.processes_count, 0, "Processes should be skipped");
+}